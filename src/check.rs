@@ -29,13 +29,12 @@
 use std::io;
 use clap::{ArgMatches, SubCommand, Arg, App};
 use serial_unit_testing::utils;
-use serial_unit_testing::serial::{Serial, CheckSettings};
+use serial_unit_testing::serial::CheckSettings;
 use crate::commands;
+use crate::error::Error;
 
-pub fn run(matches: &ArgMatches) -> Result<(), String> {
-    let (settings, port_name) = commands::get_serial_settings(matches).unwrap();
-
-    let mut serial = Serial::open_with_settings(port_name, &settings)?;
+pub fn run(matches: &ArgMatches) -> Result<(), Error> {
+    let mut serial = commands::open_serial(matches)?;
 
     let mut text = matches.value_of("text").unwrap().to_string();
     let response = matches.value_of("response").unwrap();
@@ -45,6 +44,7 @@ pub fn run(matches: &ArgMatches) -> Result<(), String> {
 
     let input_format = commands::get_text_input_format(matches);
     let output_format = commands::get_text_output_format(matches);
+    let framing = commands::get_framing(matches)?;
 
     if matches.is_present("newline") {
         text.push_str("\n");
@@ -61,25 +61,28 @@ pub fn run(matches: &ArgMatches) -> Result<(), String> {
     let check_settings = CheckSettings {
         ignore_case,
         input_format,
-        output_format
+        output_format,
+        framing
     };
 
     let (result, actual_response) = match serial.check_with_settings(&text, &response, &check_settings) {
         Ok((result, actual_response)) => (result, actual_response),
-        Err(ref e) if e.kind() == io::ErrorKind::TimedOut => return Err("Serial connection timed out".to_string()),
-        Err(e) => return Err(format!("Error running check {:?}", e))
+        Err(ref e) if e.kind() == io::ErrorKind::TimedOut => return Err(Error::Timeout("Serial connection timed out".to_string())),
+        Err(e) => return Err(Error::Other(format!("Error running check {:?}", e)))
     };
 
     if echo_text {
         println!("{}", text);
     }
 
-    if result {
-        println!("OK");
-    } else {
+    if result == false {
         println!("Mismatch: '{}' does not match '{}'", response, actual_response);
+
+        return Err(Error::TestFailure);
     }
 
+    println!("OK");
+
     Ok(())
 }
 
@@ -89,6 +92,7 @@ pub fn command<'a>() -> App<'a, 'a> {
         .args(commands::serial_arguments(false, true).as_slice())
         .args(commands::text_input_arguments().as_slice())
         .args(commands::text_output_arguments().as_slice())
+        .args(commands::framing_arguments().as_slice())
         .arg(Arg::with_name("echo")
             .long("echo")
             .short("e")
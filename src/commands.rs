@@ -26,10 +26,19 @@
  * SOFTWARE.
  */
 
+use std::io;
+use std::time::Duration;
+use std::path::{Path, PathBuf};
 use clap::{Arg, ArgMatches};
 
-use serial_unit_testing::serial::settings::{Settings, DataBits, FlowControl, Parity, StopBits};
+use serial_unit_testing::parser::Parser;
+use serial_unit_testing::serial::{Serial, Framing};
+use serial_unit_testing::serial::settings::{Settings, PartialSettings};
+use serial_unit_testing::serial::transport;
+use serial_unit_testing::tests::{Script, TestCaseSettings};
 use serial_unit_testing::utils;
+use serial_unit_testing::config::{Config, PortProfile};
+use crate::error::Error;
 
 pub fn serial_arguments<'a>(multi_port: bool, modifier_arguments: bool) -> Vec<Arg<'a, 'a>> {
     let databits = [ "5", "6", "7", "8" ];
@@ -37,9 +46,15 @@ pub fn serial_arguments<'a>(multi_port: bool, modifier_arguments: bool) -> Vec<A
     let stopbits = [ "1", "2" ];
     let flowcontrols = [ "none", "software", "hardware" ];
 
+    let port_help = if multi_port {
+        "Serial port OS specific name, tcp://<host>:<port>, pty://, mock://, usb:<vid>:<pid>, usb-serial:<serial> or product:<product>, <name>=<port> assigns a port declared in the script"
+    } else {
+        "Serial port OS specific name, tcp://<host>:<port>, pty://, mock://, usb:<vid>:<pid>, usb-serial:<serial> or product:<product>"
+    };
+
     let mut args = vec![Arg::with_name("port")
-            .help("Serial port OS specific name")
-            .required(true)
+            .help(port_help)
+            .required(multi_port == false)
             .multiple(multi_port)
             .takes_value(true),
         Arg::with_name("baud")
@@ -80,7 +95,11 @@ pub fn serial_arguments<'a>(multi_port: bool, modifier_arguments: bool) -> Vec<A
             .short("t")
             .help("Set serial port timeout duration")
             .takes_value(true)
-            .default_value("1000")
+            .default_value("1000"),
+        Arg::with_name("config")
+            .long("config")
+            .help("Configuration file to use instead of the sut.toml found in the working directory or its parents")
+            .takes_value(true)
     ];
 
     if modifier_arguments {
@@ -133,66 +152,222 @@ pub fn text_output_arguments<'a>() -> Vec<Arg<'a, 'a>> {
     ]
 }
 
-pub fn get_serial_settings<'a>(matches: &'a ArgMatches) -> Result<(Settings, &'a str), String> {
+pub fn framing_arguments<'a>() -> Vec<Arg<'a, 'a>> {
+    vec![Arg::with_name("terminator")
+            .long("terminator")
+            .help("Read the response until the terminator, e.g. \\r\\n")
+            .takes_value(true),
+        Arg::with_name("length")
+            .long("length")
+            .help("Read the response until the number of bytes is received")
+            .takes_value(true)
+            .conflicts_with("terminator"),
+        Arg::with_name("idle")
+            .long("idle")
+            .help("Read the response until no data is received for the duration in milliseconds")
+            .takes_value(true)
+            .conflicts_with_all(&["terminator", "length"])
+    ]
+}
+
+/// Get the serial settings of a port. Settings of the configuration, the port profile and the script are applied in
+/// this order and are overridden by flags given on the command line.
+pub fn get_serial_settings(matches: &ArgMatches, config: Option<&Config>, profile: Option<&PortProfile>, script: Option<&PartialSettings>) -> Result<Settings, String> {
     let mut settings: Settings = Default::default();
 
-    let port_name = matches.value_of("port").unwrap();
-    let baud_rate = matches.value_of("baud").unwrap();
-    let timeout = matches.value_of("timeout").unwrap();
-    let data_bits = matches.value_of("databits").unwrap();
-    let parity = matches.value_of("parity").unwrap();
-    let stop_bits = matches.value_of("stopbits").unwrap();
-    let flow_control = matches.value_of("flowcontrol").unwrap();
+    if let Some(config) = config {
+        config.serial.apply(&mut settings);
+    }
 
-    if let Ok(rate) = baud_rate.parse::<u32>() {
-        settings.baud_rate = rate.into();
-    } else {
-        return Err(format!("Invalid baud rate '{}'", baud_rate));
+    if let Some(profile) = profile {
+        profile.settings.apply(&mut settings);
     }
 
-    if let Ok(duration) = timeout.parse::<u64>() {
-        settings.timeout = duration;
-    } else {
-        return Err(format!("Invalid timeout '{}'", timeout));
+    if let Some(script) = script {
+        script.apply(&mut settings);
+    }
+
+    // flags not given on the command line keep the configured settings
+    let flag = |name| if matches.occurrences_of(name) > 0 { matches.value_of(name) } else { None };
+
+    if let Some(baud_rate) = flag("baud") {
+        if let Ok(rate) = baud_rate.parse::<u32>() {
+            settings.baud_rate = rate;
+        } else {
+            return Err(format!("Invalid baud rate '{}'", baud_rate));
+        }
+    }
+
+    if let Some(timeout) = flag("timeout") {
+        if let Ok(duration) = timeout.parse::<u64>() {
+            settings.timeout = duration;
+        } else {
+            return Err(format!("Invalid timeout '{}'", timeout));
+        }
+    }
+
+    if let Some(data_bits) = flag("databits") {
+        settings.data_bits = data_bits.parse()?;
+    }
+
+    if let Some(parity) = flag("parity") {
+        settings.parity = parity.parse()?;
+    }
+
+    if let Some(stop_bits) = flag("stopbits") {
+        settings.stop_bits = stop_bits.parse()?;
+    }
+
+    if let Some(flow_control) = flag("flowcontrol") {
+        settings.flow_control = flow_control.parse()?;
     }
 
-    settings.data_bits = match data_bits {
-        "5" => DataBits::Five,
-        "6" => DataBits::Six,
-        "7" => DataBits::Seven,
-        "8" => DataBits::Eight,
-        _ => {
-            return Err(format!("Invalid data bits '{}'", data_bits));
+    Ok(settings)
+}
+
+/// Load the configuration file given with `--config`, otherwise the `sut.toml` found in the working directory or
+/// its parents.
+pub fn load_config(matches: &ArgMatches) -> Result<Option<Config>, Error> {
+    let path = match matches.value_of("config") {
+        Some(path) => PathBuf::from(path),
+        None => match Config::find_from_current_dir() {
+            Some(path) => path,
+            None => return Ok(None)
         }
     };
 
-    settings.parity = match parity {
-        "none" => Parity::None,
-        "even" => Parity::Even,
-        "odd" => Parity::Odd,
-        _ => {
-            return Err(format!("Invalid parity '{}'", parity));
+    match Config::load(path) {
+        Ok(config) => Ok(Some(config)),
+        Err(e) => Err(Error::Other(e))
+    }
+}
+
+pub fn get_framing(matches: &ArgMatches) -> Result<Option<Framing>, String> {
+    if let Some(terminator) = matches.value_of("terminator") {
+        if terminator.is_empty() {
+            return Err("Terminator must not be empty".to_string());
         }
+
+        return Ok(Some(Framing::Terminator(utils::escape_text(terminator.to_string()).into_bytes())));
+    }
+
+    if let Some(length) = matches.value_of("length") {
+        return match length.parse::<usize>() {
+            Ok(length) if length > 0 => Ok(Some(Framing::Length(length))),
+            _ => Err(format!("Invalid length '{}'", length))
+        };
+    }
+
+    if let Some(idle) = matches.value_of("idle") {
+        return match idle.parse::<u64>() {
+            Ok(duration) => Ok(Some(Framing::Idle(Duration::from_millis(duration)))),
+            Err(_) => Err(format!("Invalid idle duration '{}'", idle))
+        };
+    }
+
+    Ok(None)
+}
+
+pub fn open_serial(matches: &ArgMatches) -> Result<Serial, Error> {
+    let config = load_config(matches)?;
+
+    open_port_argument(matches, matches.value_of("port").unwrap(), config.as_ref(), None)
+}
+
+/// Serial ports together with their names.
+pub type NamedPorts = Vec<(String, Serial)>;
+
+/// Open all ports given as arguments with the same settings, together with their names.
+///
+/// Ports given as `name=port` are assigned to the port declared with the name in the script and are returned
+/// separately with the declared name. Serial settings declared by the script are used for its ports.
+pub fn open_serial_ports(matches: &ArgMatches, config: Option<&Config>, script: &Script) -> Result<(NamedPorts, NamedPorts), Error> {
+    let mut ports = Vec::new();
+    let mut named_ports = Vec::new();
+
+    for argument in matches.values_of("port").into_iter().flatten() {
+        match split_port_argument(argument) {
+            (Some(name), port_name) => {
+                let settings = script.port(name).map(|port| &port.settings);
+
+                named_ports.push((name.to_string(), open_port_argument(matches, port_name, config, settings)?));
+            },
+            (None, port_name) => ports.push((port_name.to_string(), open_port_argument(matches, port_name, config, Some(&script.settings))?))
+        };
+    }
+
+    Ok((ports, named_ports))
+}
+
+/// Open the port of the profile with the name, if the configuration has one.
+pub fn open_profile(matches: &ArgMatches, name: &str, config: Option<&Config>, script: Option<&PartialSettings>) -> Result<Option<Serial>, Error> {
+    match config.and_then(|config| config.port(name)) {
+        Some(_) => open_port_argument(matches, name, config, script).map(Some),
+        None => Ok(None)
+    }
+}
+
+/// Open the port given as argument, arguments naming a port profile of the configuration open the port of the profile.
+fn open_port_argument(matches: &ArgMatches, argument: &str, config: Option<&Config>, script: Option<&PartialSettings>) -> Result<Serial, Error> {
+    let profile = config.and_then(|config| config.port(argument));
+
+    let port_name = match profile.map(|profile| profile.port_name()) {
+        Some(Ok(Some(port_name))) => port_name,
+        Some(Ok(None)) => return Err(Error::Other(format!("Port profile '{}' has no port or usb id", argument))),
+        Some(Err(e)) => return Err(Error::PortError(format!("{}: {}", argument, e))),
+        None => argument.to_string()
     };
 
-    settings.stop_bits = match stop_bits {
-        "1" => StopBits::One,
-        "2" => StopBits::Two,
-        _ => {
-            return Err(format!("Invalid stop bits '{}", stop_bits));
+    let settings = get_serial_settings(matches, config, profile, script)?;
+
+    open_port(&port_name, &settings)
+}
+
+/// Split a port argument into the declared port name and the port, e.g. `gateway=/dev/ttyUSB0`.
+fn split_port_argument(argument: &str) -> (Option<&str>, &str) {
+    if let Some(index) = argument.find('=') {
+        let name = &argument[..index];
+
+        if name.is_empty() == false && name.chars().all(|ch| ch.is_alphanumeric() || ch == '-' || ch == '_') {
+            return (Some(name), &argument[index + 1..]);
         }
+    }
+
+    (None, argument)
+}
+
+fn open_port(port_name: &str, settings: &Settings) -> Result<Serial, Error> {
+    let serial = match Serial::open_with_settings(port_name, settings) {
+        Ok(serial) => serial,
+        Err(e) => return Err(Error::PortError(format!("{}: {}", port_name, e)))
     };
 
-    settings.flow_control = match flow_control {
-        "none" => FlowControl::None,
-        "software" => FlowControl::Software,
-        "hardware" => FlowControl::Hardware,
-        _ => {
-            return Err(format!("Invalid flow control '{}'", flow_control));
+    if port_name.starts_with(transport::PTY_PREFIX) {
+        println!("Opened pseudo terminal {}", serial.name().unwrap_or_default());
+    }
+
+    Ok(serial)
+}
+
+/// Parse the script given as file argument, `-` reads the script from stdin.
+pub fn parse_script(matches: &ArgMatches, default_test_settings: TestCaseSettings) -> Result<Script, Error> {
+    let filename = matches.value_of("file").unwrap();
+    let parser = Parser::new().default_test_settings(default_test_settings);
+
+    let result = if filename == "-" {
+        parser.source_name("<stdin>").parse_script_reader(io::stdin())
+    } else {
+        if Path::new(filename).is_file() == false {
+            return Err(Error::Other("File not found".to_string()));
         }
+
+        parser.source_name(filename).parse_script_path(filename)
     };
 
-    Ok((settings, port_name))
+    match result {
+        Ok(script) => Ok(script),
+        Err(diagnostics) => Err(Error::ParseError(diagnostics.render()))
+    }
 }
 
 pub fn get_text_format(matches: &ArgMatches) -> utils::TextFormat {
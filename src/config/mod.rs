@@ -0,0 +1,214 @@
+/*
+ * File: src/config/mod.rs
+ * Date: 16.10.2026
+ * Author: agent
+ * 
+ * MIT License
+ * 
+ * Copyright (c) 2026 MarkAtk
+ * 
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+ * of the Software, and to permit persons to whom the Software is furnished to do
+ * so, subject to the following conditions:
+ * 
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ * 
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::parser::{self, Error};
+use crate::serial::usb;
+use crate::serial::settings::PartialSettings;
+use crate::tests::TestCaseSettings;
+
+mod toml;
+
+use self::toml::{Entry, Value};
+
+/// Name of the configuration file searched in the working directory and its parents.
+pub const CONFIG_FILE_NAME: &str = "sut.toml";
+
+/// Port used by its name instead of a port name, with its serial settings.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PortProfile {
+    /// OS specific name of the port or any other port name accepted on the command line.
+    pub port: Option<String>,
+    /// USB port selector, used if no port name is set.
+    pub usb: Option<usb::Selector>,
+    pub settings: PartialSettings
+}
+
+impl PortProfile {
+    /// Get the name of the port to open, USB ports are looked up by their id.
+    pub fn port_name(&self) -> Result<Option<String>, String> {
+        if let Some(ref port) = self.port {
+            return Ok(Some(port.clone()));
+        }
+
+        match self.usb {
+            Some(ref selector) => selector.find_port().map(Some).map_err(|e| format!("{}: {}", selector, e)),
+            None => Ok(None)
+        }
+    }
+}
+
+/// Project configuration read from `sut.toml`.
+///
+/// The `[serial]` table sets the serial settings of all ports, each `[ports.<name>]` table defines a port profile
+/// used by its name instead of a port and `[tests]` sets default test options written as in scripts.
+#[derive(Debug, Clone, Default)]
+pub struct Config {
+    pub serial: PartialSettings,
+    /// Port profiles by name, in order of definition.
+    pub ports: Vec<(String, PortProfile)>,
+    pub test_settings: TestCaseSettings
+}
+
+impl Config {
+    pub fn parse_str(content: &str) -> Result<Config, String> {
+        let mut config = Config::default();
+
+        for table in toml::parse(content)? {
+            if table.name == "serial" {
+                config.serial = parse_profile(&table.entries, false)?.settings;
+            } else if table.name == "tests" {
+                config.test_settings = parse_test_settings(&table.entries)?;
+            } else if table.name.starts_with("ports.") && table.name.matches('.').count() == 1 {
+                let name = table.name["ports.".len()..].to_string();
+
+                config.ports.push((name, parse_profile(&table.entries, true)?));
+            } else if table.name.is_empty() == false {
+                return Err(format!("line {}: unknown table '{}'", table.line, table.name));
+            } else if let Some(entry) = table.entries.first() {
+                return Err(format!("line {}: key '{}' must be part of a table", entry.line, entry.key));
+            }
+        }
+
+        Ok(config)
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Config, String> {
+        let content = match fs::read_to_string(path.as_ref()) {
+            Ok(content) => content,
+            Err(e) => return Err(format!("Unable to read {}: {}", path.as_ref().display(), e))
+        };
+
+        match Config::parse_str(&content) {
+            Ok(config) => Ok(config),
+            Err(e) => Err(format!("Invalid configuration {}, {}", path.as_ref().display(), e))
+        }
+    }
+
+    /// Find the configuration file in the directory or the closest parent directory.
+    pub fn find<P: AsRef<Path>>(dir: P) -> Option<PathBuf> {
+        dir.as_ref().ancestors()
+            .map(|dir| dir.join(CONFIG_FILE_NAME))
+            .find(|path| path.is_file())
+    }
+
+    /// Find the configuration file starting at the working directory.
+    pub fn find_from_current_dir() -> Option<PathBuf> {
+        env::current_dir().ok().and_then(Config::find)
+    }
+
+    /// Get the port profile with the name.
+    pub fn port(&self, name: &str) -> Option<&PortProfile> {
+        self.ports.iter().find(|(port, _)| port == name).map(|(_, profile)| profile)
+    }
+}
+
+fn parse_profile(entries: &[Entry], named: bool) -> Result<PortProfile, String> {
+    let mut profile = PortProfile::default();
+
+    for entry in entries {
+        let invalid = || format!("line {}: invalid value for '{}'", entry.line, entry.key);
+
+        match (entry.key.as_str(), &entry.value) {
+            ("port", Value::String(port)) if named => profile.port = Some(port.clone()),
+            ("usb", Value::String(id)) if named => profile.usb = Some(parse_usb(id).ok_or_else(invalid)?),
+            ("baud", &Value::Integer(baud_rate)) if baud_rate > 0 && baud_rate <= u32::MAX as i64 => profile.settings.baud_rate = Some(baud_rate as u32),
+            ("timeout", &Value::Integer(timeout)) if timeout >= 0 => profile.settings.timeout = Some(timeout as u64),
+            ("data-bits", value) => profile.settings.data_bits = Some(value.to_script_value().parse().map_err(|_| invalid())?),
+            ("parity", Value::String(parity)) => profile.settings.parity = Some(parity.parse().map_err(|_| invalid())?),
+            ("stop-bits", value) => profile.settings.stop_bits = Some(value.to_script_value().parse().map_err(|_| invalid())?),
+            ("flow-control", Value::String(flow_control)) => profile.settings.flow_control = Some(flow_control.parse().map_err(|_| invalid())?),
+            ("port", _) | ("usb", _) if named == false => return Err(format!("line {}: '{}' is only valid for port profiles", entry.line, entry.key)),
+            ("port", _) | ("usb", _) | ("baud", _) | ("timeout", _) | ("parity", _) | ("flow-control", _) => return Err(invalid()),
+            _ => return Err(format!("line {}: unknown serial setting '{}'", entry.line, entry.key))
+        };
+    }
+
+    Ok(profile)
+}
+
+fn parse_test_settings(entries: &[Entry]) -> Result<TestCaseSettings, String> {
+    let mut settings = TestCaseSettings::default();
+
+    for entry in entries {
+        // ports are declared by scripts
+        if entry.key == "port" {
+            return Err(format!("line {}: unknown test option 'port'", entry.line));
+        }
+
+        match parser::set_test_option_value(&entry.key, &entry.value.to_script_value(), &mut settings) {
+            Ok(()) => (),
+            Err(Error::UnknownTestOption(_, _, _)) => return Err(format!("line {}: unknown test option '{}'", entry.line, entry.key)),
+            Err(_) => return Err(format!("line {}: invalid value for test option '{}'", entry.line, entry.key))
+        };
+    }
+
+    Ok(settings)
+}
+
+/// Parse the `usb` key of a port profile, either a USB id like `0403:6001` or any port selector.
+fn parse_usb(value: &str) -> Option<usb::Selector> {
+    if let Some((vid, pid)) = usb::parse_id(value) {
+        return Some(usb::Selector::Id(vid, pid));
+    }
+
+    value.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use super::Config;
+    use crate::serial::settings::{Settings, Parity};
+    use crate::serial::usb;
+    use crate::tests::MatchMode;
+
+    #[test]
+    fn parse_config() {
+        let content = "[serial]\nbaud = 115200\ntimeout = 500\n\n[ports.gateway]\nport = \"/dev/ttyUSB0\"\nparity = \"even\"\n\n[ports.node]\nusb = \"0403:6001\"\n\n[tests]\ntimeout = \"200ms\"\nignore-case = true\nmatch = \"contains\"\n";
+        let config = Config::parse_str(content).unwrap();
+
+        let mut settings = Settings::default();
+        config.serial.apply(&mut settings);
+        config.port("gateway").unwrap().settings.apply(&mut settings);
+
+        assert_eq!(settings.baud_rate, 115200);
+        assert_eq!(settings.timeout, 500);
+        assert_eq!(settings.parity, Parity::Even);
+        assert_eq!(config.port("gateway").unwrap().port, Some("/dev/ttyUSB0".to_string()));
+        assert_eq!(config.port("node").unwrap().usb, Some(usb::Selector::Id(0x0403, 0x6001)));
+        assert_eq!(config.test_settings.timeout, Some(Duration::from_millis(200)));
+        assert_eq!(config.test_settings.ignore_case, Some(true));
+        assert_eq!(config.test_settings.match_mode, Some(MatchMode::Contains));
+
+        assert_eq!(Config::parse_str("[serial]\nparity = \"mark\"\n").unwrap_err(), "line 2: invalid value for 'parity'");
+        assert_eq!(Config::parse_str("[tests]\ntimout = \"1s\"\n").unwrap_err(), "line 2: unknown test option 'timout'");
+    }
+}
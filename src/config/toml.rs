@@ -0,0 +1,242 @@
+/*
+ * File: src/config/toml.rs
+ * Date: 16.10.2026
+ * Author: agent
+ * 
+ * MIT License
+ * 
+ * Copyright (c) 2026 MarkAtk
+ * 
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+ * of the Software, and to permit persons to whom the Software is furnished to do
+ * so, subject to the following conditions:
+ * 
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ * 
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+/// Value of a key in a configuration file.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    String(String),
+    Integer(i64),
+    Boolean(bool)
+}
+
+impl Value {
+    /// Get the value as written in scripts, strings are used as is.
+    pub fn to_script_value(&self) -> String {
+        match *self {
+            Value::String(ref value) => value.clone(),
+            Value::Integer(value) => value.to_string(),
+            Value::Boolean(value) => value.to_string()
+        }
+    }
+}
+
+/// Key and value with the line it was read from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    pub key: String,
+    pub value: Value,
+    pub line: usize
+}
+
+/// Table of a configuration file, keys before the first table header belong to the unnamed root table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Table {
+    /// Dotted name of the table, e.g. `ports.gateway`.
+    pub name: String,
+    pub entries: Vec<Entry>,
+    pub line: usize
+}
+
+/// Read the TOML subset used by configuration files: tables, comments and keys with string, integer or boolean values.
+pub fn parse(content: &str) -> Result<Vec<Table>, String> {
+    let mut tables = vec!(Table { name: String::new(), entries: Vec::new(), line: 0 });
+
+    for (index, line) in content.lines().enumerate() {
+        let line_number = index + 1;
+        let line = strip_comment(line).trim();
+
+        if line.is_empty() {
+            continue;
+        }
+
+        if line.starts_with('[') {
+            if line.ends_with(']') == false || line.starts_with("[[") {
+                return Err(format!("line {}: invalid table header", line_number));
+            }
+
+            let name = parse_table_name(&line[1..line.len() - 1]).map_err(|e| format!("line {}: {}", line_number, e))?;
+
+            if tables.iter().any(|table| table.name == name) {
+                return Err(format!("line {}: table '{}' is defined twice", line_number, name));
+            }
+
+            tables.push(Table { name, entries: Vec::new(), line: line_number });
+
+            continue;
+        }
+
+        let separator = match line.find('=') {
+            Some(separator) => separator,
+            None => return Err(format!("line {}: expected key = value", line_number))
+        };
+
+        let key = line[..separator].trim();
+
+        if is_bare_key(key) == false {
+            return Err(format!("line {}: invalid key '{}'", line_number, key));
+        }
+
+        let value = parse_value(line[separator + 1..].trim()).map_err(|e| format!("line {}: {}", line_number, e))?;
+        let table = tables.last_mut().unwrap();
+
+        if table.entries.iter().any(|entry| entry.key == key) {
+            return Err(format!("line {}: key '{}' is defined twice", line_number, key));
+        }
+
+        table.entries.push(Entry {
+            key: key.to_string(),
+            value,
+            line: line_number
+        });
+    }
+
+    Ok(tables)
+}
+
+/// Remove a comment from the line, hash signs in strings are kept.
+fn strip_comment(line: &str) -> &str {
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+
+    for (index, ch) in line.char_indices() {
+        match quote {
+            Some('"') if escaped => escaped = false,
+            Some('"') if ch == '\\' => escaped = true,
+            Some(delimiter) if ch == delimiter => quote = None,
+            Some(_) => (),
+            None if ch == '"' || ch == '\'' => quote = Some(ch),
+            None if ch == '#' => return &line[..index],
+            None => ()
+        };
+    }
+
+    line
+}
+
+fn is_bare_key(key: &str) -> bool {
+    key.is_empty() == false && key.chars().all(|ch| ch.is_ascii_alphanumeric() || ch == '-' || ch == '_')
+}
+
+fn parse_table_name(name: &str) -> Result<String, String> {
+    let parts: Vec<&str> = name.split('.').map(|part| part.trim()).collect();
+
+    if parts.iter().all(|part| is_bare_key(part)) == false {
+        return Err(format!("invalid table name '{}'", name.trim()));
+    }
+
+    Ok(parts.join("."))
+}
+
+fn parse_value(value: &str) -> Result<Value, String> {
+    match value {
+        "true" => return Ok(Value::Boolean(true)),
+        "false" => return Ok(Value::Boolean(false)),
+        "" => return Err("missing value".to_string()),
+        _ => ()
+    };
+
+    if value.starts_with('"') {
+        return parse_basic_string(value).map(Value::String);
+    }
+
+    if let Some(literal) = value.strip_prefix('\'') {
+        return match literal.find('\'') {
+            Some(end) if end + 1 == literal.len() => Ok(Value::String(literal[..end].to_string())),
+            _ => Err(format!("invalid string {}", value))
+        };
+    }
+
+    match value.replace('_', "").parse::<i64>() {
+        Ok(number) => Ok(Value::Integer(number)),
+        Err(_) => Err(format!("unsupported value {}", value))
+    }
+}
+
+/// Parse a string in double quotes resolving all escape sequences.
+fn parse_basic_string(value: &str) -> Result<String, String> {
+    let mut result = String::new();
+    let mut chars = value[1..].chars();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '"' => {
+                if chars.next().is_some() {
+                    return Err(format!("unexpected content after string {}", value));
+                }
+
+                return Ok(result);
+            },
+            '\\' => {
+                let escaped = match chars.next() {
+                    Some('n') => '\n',
+                    Some('r') => '\r',
+                    Some('t') => '\t',
+                    Some('\\') => '\\',
+                    Some('"') => '"',
+                    Some('u') => {
+                        let code: String = chars.by_ref().take(4).collect();
+
+                        match u32::from_str_radix(&code, 16).ok().and_then(std::char::from_u32) {
+                            Some(ch) if code.len() == 4 => ch,
+                            _ => return Err(format!("invalid unicode escape in string {}", value))
+                        }
+                    },
+                    _ => return Err(format!("invalid escape sequence in string {}", value))
+                };
+
+                result.push(escaped);
+            },
+            _ => result.push(ch)
+        };
+    }
+
+    Err(format!("missing closing quotation mark in string {}", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Value;
+
+    #[test]
+    fn parse_tables_and_values() {
+        let content = "# serial settings\n[serial]\nbaud = 115_200\nparity = \"even\" # comment\n\n[ports.gateway]\nport = '/dev/tty#1'\nterminator = \"\\\"\\\\r\\\\n\\\"\"\nrts = false\n";
+        let tables = super::parse(content).unwrap();
+
+        assert_eq!(tables.len(), 3);
+        assert_eq!(tables[1].name, "serial");
+        assert_eq!(tables[1].entries[0].value, Value::Integer(115200));
+        assert_eq!(tables[1].entries[1].value, Value::String("even".to_string()));
+        assert_eq!(tables[2].name, "ports.gateway");
+        assert_eq!(tables[2].entries[0].value, Value::String("/dev/tty#1".to_string()));
+        assert_eq!(tables[2].entries[1].value, Value::String("\"\\r\\n\"".to_string()));
+        assert_eq!(tables[2].entries[2].line, 9);
+
+        assert_eq!(super::parse("[serial]\nbaud 9600\n"), Err("line 2: expected key = value".to_string()));
+        assert_eq!(super::parse("[serial]\nbaud = 1\nbaud = 2\n"), Err("line 3: key 'baud' is defined twice".to_string()));
+    }
+}
@@ -0,0 +1,74 @@
+/*
+ * File: src/error.rs
+ * Date: 15.10.2026
+ * Author: agent
+ * 
+ * MIT License
+ * 
+ * Copyright (c) 2026 MarkAtk
+ * 
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+ * of the Software, and to permit persons to whom the Software is furnished to do
+ * so, subject to the following conditions:
+ * 
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ * 
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+use std::fmt;
+
+pub const EXIT_TEST_FAILURE: i32 = 1;
+pub const EXIT_OTHER_ERROR: i32 = 2;
+pub const EXIT_PARSE_ERROR: i32 = 3;
+pub const EXIT_PORT_ERROR: i32 = 4;
+pub const EXIT_TIMEOUT: i32 = 5;
+
+#[derive(Debug)]
+pub enum Error {
+    TestFailure,
+    ParseError(String),
+    PortError(String),
+    Timeout(String),
+    Other(String)
+}
+
+impl Error {
+    pub fn exit_code(&self) -> i32 {
+        match *self {
+            Error::TestFailure => EXIT_TEST_FAILURE,
+            Error::ParseError(_) => EXIT_PARSE_ERROR,
+            Error::PortError(_) => EXIT_PORT_ERROR,
+            Error::Timeout(_) => EXIT_TIMEOUT,
+            Error::Other(_) => EXIT_OTHER_ERROR
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::TestFailure => formatter.write_str("Tests failed"),
+            Error::ParseError(ref message) => formatter.write_fmt(format_args!("Unable to parse file\n\n{}", message)),
+            Error::PortError(ref message) => formatter.write_str(message),
+            Error::Timeout(ref message) => formatter.write_str(message),
+            Error::Other(ref message) => formatter.write_str(message)
+        }
+    }
+}
+
+impl From<String> for Error {
+    fn from(message: String) -> Error {
+        Error::Other(message)
+    }
+}
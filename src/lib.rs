@@ -30,6 +30,9 @@ pub mod utils;
 pub mod tests;
 pub mod serial;
 pub mod parser;
+pub mod simulator;
+pub mod report;
+pub mod config;
 
 #[cfg(test)]
 mod library_tests {
@@ -28,11 +28,15 @@
 
 use clap::{ArgMatches, App, SubCommand, Arg};
 use serialport::{self, SerialPortType};
+use crate::error::Error;
 
-pub fn run(matches: &ArgMatches) -> Result<(), String> {
+pub fn run(matches: &ArgMatches) -> Result<(), Error> {
     let verbose = matches.is_present("verbose");
 
-    let ports = serialport::available_ports().unwrap();
+    let ports = match serialport::available_ports() {
+        Ok(ports) => ports,
+        Err(e) => return Err(Error::PortError(format!("Unable to list ports: {}", e)))
+    };
 
     for port in ports {
         println!("{}", port.port_name);
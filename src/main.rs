@@ -31,9 +31,11 @@ extern crate clap;
 
 extern crate serial_unit_testing;
 
+use std::process;
 use clap::{App, ArgMatches, AppSettings};
 
 mod commands;
+mod error;
 
 mod list;
 mod send;
@@ -41,8 +43,11 @@ mod monitor;
 mod check;
 mod run;
 mod verify;
+mod simulate;
 
-fn run(matches: ArgMatches) -> Result<(), String> {
+use crate::error::Error;
+
+fn run(matches: ArgMatches) -> Result<(), Error> {
     match matches.subcommand() {
         ("send", Some(m)) => send::run(m),
         ("list", Some(m)) => list::run(m),
@@ -50,6 +55,7 @@ fn run(matches: ArgMatches) -> Result<(), String> {
         ("check", Some(m)) => check::run(m),
         ("run", Some(m)) => run::run(m),
         ("verify", Some(m)) => verify::run(m),
+        ("simulate", Some(m)) => simulate::run(m),
         _ => Ok(())
     }
 }
@@ -67,11 +73,27 @@ fn main() {
         .subcommand(check::command())
         .subcommand(run::command())
         .subcommand(verify::command())
-        .get_matches();
+        .subcommand(simulate::command())
+        .get_matches_safe();
+
+    let matches = match matches {
+        Ok(matches) => matches,
+        // help and version are displayed on stdout and exit successfully
+        Err(e) if e.use_stderr() == false => e.exit(),
+        Err(e) => {
+            eprintln!("{}", e.message);
+
+            process::exit(error::EXIT_OTHER_ERROR);
+        }
+    };
 
     if let Err(e) = run(matches) {
-        println!("Error: {}", e);
+        // failed tests are already reported by the command itself
+        match e {
+            Error::TestFailure => (),
+            _ => eprintln!("Error: {}", e)
+        };
 
-        return;
+        process::exit(e.exit_code());
     }
 }
@@ -31,18 +31,14 @@ use clap::{ArgMatches, App, SubCommand};
 use serial_unit_testing::utils;
 use serial_unit_testing::serial::Serial;
 use crate::commands;
+use crate::error::Error;
 
-pub fn run(matches: &ArgMatches) -> Result<(), String> {
-    let (settings, port_name) = commands::get_serial_settings(matches).unwrap();
+pub fn run(matches: &ArgMatches) -> Result<(), Error> {
+    let mut serial = commands::open_serial(matches)?;
 
-    match Serial::open_with_settings(port_name, &settings) {
-        Ok(mut serial) => {
-            let text_format = commands::get_text_output_format(matches);
+    let text_format = commands::get_text_output_format(matches);
 
-            read(&mut serial, &text_format)
-        },
-        Err(e) => return Err(format!("Error opening port {:?}", e))
-    }
+    read(&mut serial, &text_format)
 }
 
 pub fn command<'a>() -> App<'a, 'a> {
@@ -51,7 +47,7 @@ pub fn command<'a>() -> App<'a, 'a> {
         .args(commands::serial_arguments(false, true).as_slice())
 }
 
-fn read(serial: &mut Serial, text_format: &utils::TextFormat) -> Result<(), String> {
+fn read(serial: &mut Serial, text_format: &utils::TextFormat) -> Result<(), Error> {
     let mut row_entries = 0;
 
     loop {
@@ -65,7 +61,7 @@ fn read(serial: &mut Serial, text_format: &utils::TextFormat) -> Result<(), Stri
                 io::stdout().flush().unwrap();
             },
             Err(ref e) if e.kind() == io::ErrorKind::TimedOut => (),
-            Err(e) => return Err(format!("{:?}", e))
+            Err(e) => return Err(Error::Other(format!("{:?}", e)))
         }
     }
 }
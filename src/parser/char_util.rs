@@ -27,7 +27,7 @@
  */
 
 pub fn is_parenthesis(ch: char) -> bool {
-    ch == '[' || ch == ']' || ch == '(' || ch == ')'
+    ch == '[' || ch == ']' || ch == '(' || ch == ')' || ch == '{' || ch == '}'
 }
 
 pub fn is_modifier(ch: char) -> bool {
@@ -39,11 +39,11 @@ pub fn is_separator(ch: char) -> bool {
 }
 
 pub fn is_identifier(ch: char) -> bool {
-    ch.is_alphanumeric() || ch == '-' || ch == '_' || ch == ' ' || ch == '\t'
+    ch.is_alphanumeric() || ch == '-' || ch == '_' || ch == '@' || ch == ' ' || ch == '\t'
 }
 
 pub fn is_identifier_start(ch: char) -> bool {
-    ch.is_alphanumeric()
+    ch.is_alphanumeric() || ch == '@'
 }
 
 pub fn is_content_start(ch: char) -> bool {
@@ -0,0 +1,229 @@
+/*
+ * File: parser/diagnostic.rs
+ * Date: 16.10.2026
+ * Author: agent
+ * 
+ * MIT License
+ * 
+ * Copyright (c) 2026 MarkAtk
+ * 
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+ * of the Software, and to permit persons to whom the Software is furnished to do
+ * so, subject to the following conditions:
+ * 
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ * 
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+use std::fmt;
+use super::error::Error;
+use super::options::{TEST_OPTIONS, HOOK_OPTIONS, GROUP_OPTIONS, SERIAL_OPTIONS};
+use super::string_util;
+
+const STEP_KEYWORDS: [&str; 2] = ["send", "expect"];
+
+const HEADERS: [&str; 1] = ["@port"];
+
+/// Parse error with the source line and a hint to fix it.
+#[derive(Debug, PartialEq)]
+pub struct Diagnostic {
+    pub error: Error,
+    /// Name of the script containing the error, if known.
+    pub file: Option<String>,
+    pub source_line: Option<String>,
+    pub hint: Option<String>
+}
+
+impl Diagnostic {
+    pub fn new(error: Error, file: Option<String>, source_line: Option<String>) -> Diagnostic {
+        let hint = get_hint(&error);
+
+        Diagnostic {
+            error,
+            file,
+            source_line,
+            hint
+        }
+    }
+
+    /// Render the diagnostic with the source line and a caret under the error position.
+    pub fn render(&self) -> String {
+        let mut result = format!("error: {}\n", self.error);
+
+        if let Some((line, column)) = self.error.position() {
+            let file = self.file.clone().unwrap_or_default();
+            let line_number = line.to_string();
+            let padding = " ".repeat(line_number.len());
+
+            result.push_str(&format!("{}--> {}:{}:{}\n", padding, file, line, column));
+
+            if let Some(ref source_line) = self.source_line {
+                // keep tabs so the caret is aligned with the source line
+                let indent: String = source_line.chars()
+                    .take(column.saturating_sub(1) as usize)
+                    .map(|ch| if ch == '\t' { '\t' } else { ' ' })
+                    .collect();
+
+                result.push_str(&format!("{} |\n", padding));
+                result.push_str(&format!("{} | {}\n", line_number, source_line));
+                result.push_str(&format!("{} | {}^\n", padding, indent));
+            }
+
+            if let Some(ref hint) = self.hint {
+                result.push_str(&format!("{} = hint: {}\n", padding, hint));
+            }
+        } else {
+            if let Some(ref file) = self.file {
+                result.push_str(&format!(" --> {}\n", file));
+            }
+
+            if let Some(ref hint) = self.hint {
+                result.push_str(&format!(" = hint: {}\n", hint));
+            }
+        }
+
+        result
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(ref file) = self.file {
+            formatter.write_fmt(format_args!("{} in '{}'", self.error, file))
+        } else {
+            formatter.write_fmt(format_args!("{}", self.error))
+        }
+    }
+}
+
+/// All errors found while parsing a script, in order of appearance.
+#[derive(Debug, PartialEq)]
+pub struct Diagnostics {
+    diagnostics: Vec<Diagnostic>
+}
+
+impl Diagnostics {
+    pub fn new(diagnostics: Vec<Diagnostic>) -> Diagnostics {
+        Diagnostics {
+            diagnostics
+        }
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Diagnostic> {
+        self.diagnostics.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.diagnostics.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+
+    /// Render all diagnostics followed by the number of errors.
+    pub fn render(&self) -> String {
+        let mut result = String::new();
+
+        for diagnostic in &self.diagnostics {
+            result.push_str(&diagnostic.render());
+            result.push('\n');
+        }
+
+        if self.diagnostics.len() == 1 {
+            result.push_str("1 error found");
+        } else {
+            result.push_str(&format!("{} errors found", self.diagnostics.len()));
+        }
+
+        result
+    }
+}
+
+impl fmt::Display for Diagnostics {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let messages: Vec<String> = self.diagnostics.iter().map(|diagnostic| diagnostic.to_string()).collect();
+
+        formatter.write_str(&messages.join("\n"))
+    }
+}
+
+fn get_hint(error: &Error) -> Option<String> {
+    match *error {
+        Error::UnknownTestOption(ref name, _, _) => {
+            let options: Vec<&str> = TEST_OPTIONS.iter().chain(HOOK_OPTIONS.iter()).cloned().collect();
+
+            suggest("option", name, &options)
+        },
+        Error::UnknownGroupOption(ref name, _, _) => {
+            let options: Vec<&str> = GROUP_OPTIONS.iter().chain(TEST_OPTIONS.iter()).cloned().collect();
+
+            suggest("option", name, &options)
+        },
+        Error::InvalidStep(ref name, _, _) => {
+            suggest("step", name, &STEP_KEYWORDS).or_else(|| Some("steps start with 'send' or 'expect'".to_string()))
+        },
+        Error::UnknownHeader(ref name, _, _) => {
+            suggest("header", name, &HEADERS).or_else(|| Some("ports are declared with the header '[@port name]'".to_string()))
+        },
+        Error::UnknownPortOption(ref name, _, _) => suggest("option", name, SERIAL_OPTIONS),
+        Error::UnknownPort(ref name, _, _) => Some(format!("declare the port before using it with '[@port {}]'", name)),
+        Error::InvalidPortName(_, _, _) | Error::MissingPortName(_, _) => Some("port names are a single word, e.g. '[@port gateway]'".to_string()),
+        Error::InvalidOptionValue(ref expected_type, _, _) if expected_type == "port name" => Some("use the name of a declared port, e.g. port=gateway".to_string()),
+        Error::InvalidOptionValue(ref expected_type, _, _) if expected_type == "parity" => Some("use none, even or odd".to_string()),
+        Error::InvalidOptionValue(ref expected_type, _, _) if expected_type == "data bits" => Some("use 5, 6, 7 or 8".to_string()),
+        Error::InvalidOptionValue(ref expected_type, _, _) if expected_type == "stop bits" => Some("use 1 or 2".to_string()),
+        Error::InvalidOptionValue(ref expected_type, _, _) if expected_type == "flow control" => Some("use none, software or hardware".to_string()),
+        Error::InvalidOptionValue(ref expected_type, _, _) if expected_type == "time" => Some("time values are written as number with unit, e.g. 500ms, 2s or 100us".to_string()),
+        Error::InvalidOptionValue(ref expected_type, _, _) if expected_type == "boolean" => Some("use true or false".to_string()),
+        Error::InvalidOptionValue(ref expected_type, _, _) if expected_type == "terminator" => Some("terminators are written as content, e.g. \"\\r\\n\" or h\"0d0a\"".to_string()),
+        Error::InvalidAssertion(_, _, _) => Some("assertions compare a variable, e.g. \"voltage == 3.3±0.1\", \"voltage in 3.2..3.4\" or \"voltage > 3\"".to_string()),
+        Error::InvalidOptionValue(ref expected_type, _, _) if expected_type == "match mode" => Some("use exact, contains, starts-with, ends-with, line or regex-search".to_string()),
+        Error::IllegalToken(ref value, _, _) if value.starts_with('"') => Some("content is missing the closing quotation mark '\"'".to_string()),
+        Error::InvalidLineStart(_, _) => Some("lines start with a group '[', a test '(', '\"' or ':', a format specifier or 'include'".to_string()),
+        _ => None
+    }
+}
+
+fn suggest(kind: &str, name: &str, candidates: &[&str]) -> Option<String> {
+    let closest = candidates.iter()
+        .map(|option| (string_util::edit_distance(name, option), option))
+        .min_by_key(|&(distance, _)| distance);
+
+    match closest {
+        Some((distance, option)) if distance <= 2 => Some(format!("unknown {} '{}', did you mean '{}'?", kind, name, option)),
+        _ => None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Diagnostic;
+    use super::super::error::Error;
+
+    #[test]
+    fn render_unknown_option() {
+        let error = Error::UnknownTestOption("timout".to_string(), 2, 8);
+        let diagnostic = Diagnostic::new(error, Some("script.sut".to_string()), Some("(Test, timout=1s)\"a\" : \"b\"".to_string()));
+
+        let expected = "error: Unknown test option 'timout' at 2:8\n \
+            --> script.sut:2:8\n  \
+            |\n\
+            2 | (Test, timout=1s)\"a\" : \"b\"\n  \
+            |        ^\n  \
+            = hint: unknown option 'timout', did you mean 'timeout'?\n";
+
+        assert_eq!(diagnostic.render(), expected);
+    }
+}
@@ -44,9 +44,53 @@ pub enum Error {
     MissingContent(String, u32, u32),
     InvalidLineStart(u32, u32),
     InvalidOptionValue(String, u32, u32),
+    InvalidAssertion(String, u32, u32),
     InvalidOutputContent(String, u32, u32),
     UnknownTestOption(String, u32, u32),
-    UnknownGroupOption(String, u32, u32)
+    UnknownGroupOption(String, u32, u32),
+    MissingIncludePath(u32, u32),
+    IncludeFileError(String, u32, u32),
+    IncludeCycle(String, u32, u32),
+    InvalidStep(String, u32, u32),
+    UnknownHeader(String, u32, u32),
+    MissingPortName(u32, u32),
+    InvalidPortName(String, u32, u32),
+    UnknownPort(String, u32, u32),
+    UnknownPortOption(String, u32, u32)
+}
+
+impl Error {
+    /// Get line and column of the error, if any.
+    pub fn position(&self) -> Option<(u32, u32)> {
+        match *self {
+            Error::ReadFileError => None,
+            Error::UnknownError(line, column) |
+            Error::MissingDirectionSeparator(line, column) |
+            Error::MissingGroupIdentifier(line, column) |
+            Error::MissingTestIdentifier(line, column) |
+            Error::MissingOptionIdentifier(line, column) |
+            Error::MissingOptionSeparator(line, column) |
+            Error::MissingOptionValue(line, column) |
+            Error::InvalidLineStart(line, column) |
+            Error::MissingIncludePath(line, column) |
+            Error::MissingPortName(line, column) => Some((line, column)),
+            Error::IllegalToken(_, line, column) |
+            Error::MissingClosingParenthesis(_, line, column) |
+            Error::MissingContent(_, line, column) |
+            Error::InvalidOptionValue(_, line, column) |
+            Error::InvalidAssertion(_, line, column) |
+            Error::InvalidOutputContent(_, line, column) |
+            Error::UnknownTestOption(_, line, column) |
+            Error::UnknownGroupOption(_, line, column) |
+            Error::IncludeFileError(_, line, column) |
+            Error::IncludeCycle(_, line, column) |
+            Error::InvalidStep(_, line, column) |
+            Error::UnknownHeader(_, line, column) |
+            Error::InvalidPortName(_, line, column) |
+            Error::UnknownPort(_, line, column) |
+            Error::UnknownPortOption(_, line, column) => Some((line, column))
+        }
+    }
 }
 
 impl fmt::Display for Error {
@@ -65,9 +109,19 @@ impl fmt::Display for Error {
             Error::MissingContent(ref content_type, line, column) => formatter.write_fmt(format_args!("Missing test {} at {}:{}", content_type, line, column)),
             Error::InvalidLineStart(line, column) => formatter.write_fmt(format_args!("Invalid line start at {}:{}", line, column)),
             Error::InvalidOptionValue(ref expected_type, line, column) => formatter.write_fmt(format_args!("Invalid option type at {}:{}. {} type expected", line, column, expected_type)),
+            Error::InvalidAssertion(ref message, line, column) => formatter.write_fmt(format_args!("Invalid assertion at {}:{}. {}", line, column, message)),
             Error::InvalidOutputContent(ref content, line, column) => formatter.write_fmt(format_args!("Invalid output content '{}' at {}:{}", content, line, column)),
             Error::UnknownTestOption(ref name, line, column) => formatter.write_fmt(format_args!("Unknown test option '{}' at {}:{}", name, line, column)),
-            Error::UnknownGroupOption(ref name, line, column) => formatter.write_fmt(format_args!("Unknown group option '{}' at {}:{}", name, line, column))
+            Error::UnknownGroupOption(ref name, line, column) => formatter.write_fmt(format_args!("Unknown group option '{}' at {}:{}", name, line, column)),
+            Error::MissingIncludePath(line, column) => formatter.write_fmt(format_args!("Missing include path at {}:{}", line, column)),
+            Error::IncludeFileError(ref name, line, column) => formatter.write_fmt(format_args!("Unable to read included file '{}' at {}:{}", name, line, column)),
+            Error::IncludeCycle(ref name, line, column) => formatter.write_fmt(format_args!("Include cycle of file '{}' at {}:{}", name, line, column)),
+            Error::InvalidStep(ref name, line, column) => formatter.write_fmt(format_args!("Invalid test step '{}' at {}:{}", name, line, column)),
+            Error::UnknownHeader(ref name, line, column) => formatter.write_fmt(format_args!("Unknown header '{}' at {}:{}", name, line, column)),
+            Error::MissingPortName(line, column) => formatter.write_fmt(format_args!("Missing port name at {}:{}", line, column)),
+            Error::InvalidPortName(ref name, line, column) => formatter.write_fmt(format_args!("Invalid port name '{}' at {}:{}", name, line, column)),
+            Error::UnknownPort(ref name, line, column) => formatter.write_fmt(format_args!("Unknown port '{}' at {}:{}", name, line, column)),
+            Error::UnknownPortOption(ref name, line, column) => formatter.write_fmt(format_args!("Unknown port option '{}' at {}:{}", name, line, column))
         }
     }
 }
@@ -88,9 +142,19 @@ impl StdError for Error {
             Error::MissingContent(_, _, _) => "Missing test content",
             Error::InvalidLineStart(_, _) => "Invalid line start",
             Error::InvalidOptionValue(_, _, _) => "Invalid option value",
+            Error::InvalidAssertion(_, _, _) => "Invalid assertion",
             Error::InvalidOutputContent(_, _, _) => "Invalid output content",
             Error::UnknownTestOption(_, _, _) => "Unknown test option",
-            Error::UnknownGroupOption(_, _, _) => "Unknown group option"
+            Error::UnknownGroupOption(_, _, _) => "Unknown group option",
+            Error::MissingIncludePath(_, _) => "Missing include path",
+            Error::IncludeFileError(_, _, _) => "Unable to read included file",
+            Error::IncludeCycle(_, _, _) => "Include cycle",
+            Error::InvalidStep(_, _, _) => "Invalid test step",
+            Error::UnknownHeader(_, _, _) => "Unknown header",
+            Error::MissingPortName(_, _) => "Missing port name",
+            Error::InvalidPortName(_, _, _) => "Invalid port name",
+            Error::UnknownPort(_, _, _) => "Unknown port",
+            Error::UnknownPortOption(_, _, _) => "Unknown port option"
         }
     }
 }
@@ -30,7 +30,8 @@ use super::token::{Token, TokenType};
 use super::char_util;
 
 pub struct Lexer {
-    input: String,
+    // characters are indexed directly, positions in the string would be bytes
+    input: Vec<char>,
     position: usize,
     line: u32,
     column: u32
@@ -39,7 +40,7 @@ pub struct Lexer {
 impl Lexer {
     pub fn new(input: String) -> Lexer {
         Lexer {
-            input,
+            input: input.chars().collect(),
             position: 0,
             line: 1,
             column: 1
@@ -52,7 +53,8 @@ impl Lexer {
         loop {
             let token = self.next_token();
 
-            if token.token_type == TokenType::EndOfFile || token.token_type == TokenType::Illegal {
+            // continue after illegal tokens so all errors can be reported
+            if token.token_type == TokenType::EndOfFile {
                 tokens.push(token);
 
                 break;
@@ -65,20 +67,20 @@ impl Lexer {
     }
 
     fn next_token(&mut self) -> Token {
+        self.skip_whitespaces();
+
         if self.position >= self.input.len() {
             return Token::new(TokenType::EndOfFile);
         }
 
-        self.skip_whitespaces();
-
-        let ch = self.input.chars().nth(self.position).unwrap();
+        let ch = self.input[self.position];
 
         if char_util::is_comment_start(ch) {
             return self.skip_comment();
         }
 
         if char_util::is_modifier(ch) {
-            if self.position + 1 < self.input.len() && self.input.chars().nth(self.position + 1).unwrap() == '"' {
+            if self.position + 1 < self.input.len() && self.input[self.position + 1] == '"' {
                 return self.recognize_modifier(ch);
             }
         }
@@ -103,12 +105,17 @@ impl Lexer {
             return self.recognize_newline();
         }
 
-        return Token::new_with_value(TokenType::Illegal, ch.to_string(), self.line, self.column);
+        let token = Token::new_with_value(TokenType::Illegal, ch.to_string(), self.line, self.column);
+
+        self.position += 1;
+        self.column += 1;
+
+        token
     }
 
     fn skip_whitespaces(&mut self) {
         while self.position < self.input.len() {
-            let ch = self.input.chars().nth(self.position).unwrap();
+            let ch = self.input[self.position];
 
             if char_util::is_whitespace(ch) == false {
                 break;
@@ -125,7 +132,7 @@ impl Lexer {
         self.column += 1;
 
         while self.position < self.input.len() {
-            let ch = self.input.chars().nth(self.position).unwrap();
+            let ch = self.input[self.position];
 
             if char_util::is_newline(ch) {
                 return self.recognize_newline();
@@ -135,7 +142,7 @@ impl Lexer {
             self.column += 1;
         }
 
-        Token::new(TokenType::Illegal)
+        Token::new(TokenType::EndOfFile)
     }
 
     fn recognize_identifier(&mut self) -> Token {
@@ -143,13 +150,23 @@ impl Lexer {
 
         let column = self.column;
         
-        while self.position <= self.input.len() {
-            let ch = self.input.chars().nth(self.position).unwrap();
+        while self.position < self.input.len() {
+            let ch = self.input[self.position];
 
             if char_util::is_identifier(ch) == false {
                 break;
             }
 
+            // header keywords are single words, e.g. [@port baud=9600]
+            if identifier.starts_with('@') && char_util::is_whitespace(ch) {
+                break;
+            }
+
+            // content with format specifier following an identifier, e.g. send h"00"
+            if char_util::is_modifier(ch) && identifier.ends_with(char_util::is_whitespace) && self.input.get(self.position + 1) == Some(&'"') {
+                break;
+            }
+
             identifier.push(ch);
 
             self.position += 1;
@@ -169,8 +186,13 @@ impl Lexer {
         self.position += 1;
         self.column += 1;
 
-        while self.position <= self.input.len() {
-            let ch = self.input.chars().nth(self.position).unwrap();
+        loop {
+            // content without closing quotation mark
+            if self.position >= self.input.len() {
+                return Token::new_with_value(TokenType::Illegal, format!("\"{}", content), self.line, column);
+            }
+
+            let ch = self.input[self.position];
 
             if ch == '\\' && escape_next_char == false {
                 escape_next_char = true;
@@ -184,7 +206,7 @@ impl Lexer {
             }
 
             if ch == '\n' || ch == '\r' {
-                return Token::new_with_value(TokenType::Illegal, content, self.line, column);
+                return Token::new_with_value(TokenType::Illegal, format!("\"{}", content), self.line, column);
             }
 
             if ch == '"' && escape_next_char == false {
@@ -206,6 +228,8 @@ impl Lexer {
     }
 
     fn recognize_separator(&mut self, ch: char) -> Token {
+        let column = self.column;
+
         self.column += 1;
         self.position += 1;
 
@@ -216,17 +240,21 @@ impl Lexer {
             _ => TokenType::Illegal
         };
 
-        Token::new_with_value(token_type, ch.to_string(), self.line, self.column)
+        Token::new_with_value(token_type, ch.to_string(), self.line, column)
     }
 
     fn recognize_modifier(&mut self, ch: char) -> Token {
+        let column = self.column;
+
         self.column += 1;
         self.position += 1;
 
-        Token::new_with_value(TokenType::FormatSpecifier, ch.to_string(), self.line, self.column)
+        Token::new_with_value(TokenType::FormatSpecifier, ch.to_string(), self.line, column)
     }
 
     fn recognize_parenthesis(&mut self, ch: char) -> Token {
+        let column = self.column;
+
         self.column += 1;
         self.position += 1;
 
@@ -235,10 +263,12 @@ impl Lexer {
             ']' => TokenType::RightGroupParenthesis,
             '(' => TokenType::LeftTestParenthesis,
             ')' => TokenType::RightTestParenthesis,
+            '{' => TokenType::LeftBlockParenthesis,
+            '}' => TokenType::RightBlockParenthesis,
             _ => TokenType::Illegal
         };
 
-        Token::new_with_value(token_type, ch.to_string(), self.line, self.column)
+        Token::new_with_value(token_type, ch.to_string(), self.line, column)
     }
 
     fn recognize_newline(&mut self) -> Token {
@@ -251,7 +281,7 @@ impl Lexer {
         self.line += 1;
 
         if self.position < self.input.len() {
-            let next_char = self.input.chars().nth(self.position).unwrap();
+            let next_char = self.input[self.position];
             
             if next_char == '\r' {
                 value.push('\r');
@@ -28,11 +28,15 @@
 
 use std::fs;
 use std::io::{BufReader, Read};
-use regex::Regex;
-use crate::tests::{TestCase, TestSuite, TestCaseSettings, TestSuiteSettings};
+use std::path::{Path, PathBuf};
+use regex::bytes::Regex;
+use crate::tests::{TestCase, TestSuite, TestCaseSettings, TestSuiteSettings, TestHook, TestStep, Script, PortDeclaration};
+use crate::serial::settings::PartialSettings;
+use crate::tests::{variables, byte_pattern};
 use crate::utils::TextFormat;
 
 mod error;
+mod diagnostic;
 mod token;
 mod string_util;
 mod char_util;
@@ -42,59 +46,272 @@ mod options;
 
 use self::lexer::Lexer;
 use self::token::{Token, TokenType};
-use self::error::Error;
+pub use self::error::Error;
+pub use self::diagnostic::{Diagnostic, Diagnostics};
 use self::finite_state_machine::FiniteStateMachine;
-use self::options::{set_test_option, set_group_option};
+use self::options::{set_test_option, set_group_option, set_hook_option, set_serial_option};
 
-pub fn parse_file(file: &mut fs::File) -> Result<Vec<TestSuite>, Error> {
-    parse_file_with_default_settings(file, Default::default())
+/// Parser for test scripts.
+///
+/// Included files are resolved relative to the base directory, which defaults to the working directory.
+/// If a source name is set it is added to all parse errors. Parsing continues after errors, all errors found
+/// are returned.
+pub struct Parser {
+    default_test_settings: TestCaseSettings,
+    base_dir: PathBuf,
+    source_name: Option<String>
 }
 
-pub fn parse_file_with_default_settings(file: &mut fs::File, default_test_settings: TestCaseSettings) -> Result<Vec<TestSuite>, Error> {
-    let mut reader = BufReader::new(file);
-    let mut content = String::new();
+impl Parser {
+    pub fn new() -> Parser {
+        Parser {
+            default_test_settings: Default::default(),
+            base_dir: PathBuf::from("."),
+            source_name: None
+        }
+    }
+
+    /// Set the settings used for tests not setting an option themselves.
+    pub fn default_test_settings(mut self, default_test_settings: TestCaseSettings) -> Parser {
+        self.default_test_settings = default_test_settings;
 
-    if let Err(_) = reader.read_to_string(&mut content) {
-        return Err(Error::ReadFileError);
+        self
     }
 
-    let mut lexer = Lexer::new(content);
-    let tokens = lexer.get_tokens();
+    pub fn base_dir<P: AsRef<Path>>(mut self, base_dir: P) -> Parser {
+        self.base_dir = base_dir.as_ref().to_path_buf();
+
+        self
+    }
+
+    pub fn source_name(mut self, source_name: &str) -> Parser {
+        self.source_name = Some(source_name.to_string());
+
+        self
+    }
+
+    pub fn parse_str(&self, content: &str) -> Result<Vec<TestSuite>, Diagnostics> {
+        self.parse_script_str(content).map(|script| script.test_suites)
+    }
+
+    pub fn parse_reader<R: Read>(&self, reader: R) -> Result<Vec<TestSuite>, Diagnostics> {
+        self.parse_script_reader(reader).map(|script| script.test_suites)
+    }
+
+    /// Parse the script at the given path. Included files are resolved relative to the directory of the script
+    /// instead of the base directory.
+    pub fn parse_path<P: AsRef<Path>>(&self, path: P) -> Result<Vec<TestSuite>, Diagnostics> {
+        self.parse_script_path(path).map(|script| script.test_suites)
+    }
+
+    /// Parse the script including the ports it declares.
+    pub fn parse_script_str(&self, content: &str) -> Result<Script, Diagnostics> {
+        let mut context = IncludeContext {
+            base_dir: self.base_dir.clone(),
+            files: Vec::new(),
+            ports: Vec::new(),
+            settings: Default::default(),
+            group: None
+        };
+
+        self.parse(content, &mut context)
+    }
+
+    pub fn parse_script_reader<R: Read>(&self, reader: R) -> Result<Script, Diagnostics> {
+        let mut reader = BufReader::new(reader);
+        let mut content = String::new();
+
+        if reader.read_to_string(&mut content).is_err() {
+            return Err(self.read_error());
+        }
+
+        self.parse_script_str(&content)
+    }
+
+    pub fn parse_script_path<P: AsRef<Path>>(&self, path: P) -> Result<Script, Diagnostics> {
+        let path = match fs::canonicalize(path) {
+            Ok(path) => path,
+            Err(_) => return Err(self.read_error())
+        };
+
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(_) => return Err(self.read_error())
+        };
+
+        let mut context = IncludeContext {
+            base_dir: path.parent().map_or(PathBuf::from("."), |dir| dir.to_path_buf()),
+            files: vec!(path),
+            ports: Vec::new(),
+            settings: Default::default(),
+            group: None
+        };
+
+        self.parse(&content, &mut context)
+    }
+
+    fn parse(&self, content: &str, context: &mut IncludeContext) -> Result<Script, Diagnostics> {
+        let source = Source::new(self.source_name.clone(), content);
+        let mut test_suites: Vec<TestSuite> = Vec::new();
+        let mut diagnostics: Vec<Diagnostic> = Vec::new();
+
+        let mut lexer = Lexer::new(content.to_string());
+        let tokens = lexer.get_tokens();
+
+        analyse_tokens(tokens, &self.default_test_settings, &mut test_suites, &source, context, &mut diagnostics);
+
+        if diagnostics.is_empty() == false {
+            return Err(Diagnostics::new(diagnostics));
+        }
+
+        Ok(Script {
+            settings: context.settings.clone(),
+            ports: context.ports.clone(),
+            test_suites
+        })
+    }
+
+    fn read_error(&self) -> Diagnostics {
+        Diagnostics::new(vec!(Diagnostic::new(Error::ReadFileError, self.source_name.clone(), None)))
+    }
+}
+
+impl Default for Parser {
+    fn default() -> Parser {
+        Parser::new()
+    }
+}
+
+/// Files being parsed, used to resolve relative includes and to detect include cycles.
+struct IncludeContext {
+    base_dir: PathBuf,
+    files: Vec<PathBuf>,
+    /// Ports declared so far, shared with included files.
+    ports: Vec<PortDeclaration>,
+    /// Serial settings of the default port.
+    settings: PartialSettings,
+    /// Index of the test suite following tests are added to.
+    group: Option<usize>
+}
+
+/// Script being analysed, used to add the file name and source line to errors.
+struct Source {
+    name: Option<String>,
+    lines: Vec<String>
+}
+
+impl Source {
+    fn new(name: Option<String>, content: &str) -> Source {
+        Source {
+            name,
+            lines: content.lines().map(|line| line.to_string()).collect()
+        }
+    }
+
+    fn diagnostic(&self, error: Error) -> Diagnostic {
+        let source_line = error.position()
+            .and_then(|(line, _)| (line as usize).checked_sub(1))
+            .and_then(|index| self.lines.get(index))
+            .cloned();
+
+        Diagnostic::new(error, self.name.clone(), source_line)
+    }
+}
+
+pub fn parse_str(content: &str) -> Result<Vec<TestSuite>, Diagnostics> {
+    Parser::new().parse_str(content)
+}
+
+pub fn parse_reader<R: Read>(reader: R) -> Result<Vec<TestSuite>, Diagnostics> {
+    Parser::new().parse_reader(reader)
+}
+
+pub fn parse_file(file: &mut fs::File) -> Result<Vec<TestSuite>, Diagnostics> {
+    Parser::new().parse_reader(file)
+}
+
+/// Parse a script from an open file. Included files are resolved relative to the working directory.
+pub fn parse_file_with_default_settings(file: &mut fs::File, default_test_settings: TestCaseSettings) -> Result<Vec<TestSuite>, Diagnostics> {
+    Parser::new().default_test_settings(default_test_settings).parse_reader(file)
+}
+
+pub fn parse_path<P: AsRef<Path>>(path: P) -> Result<Vec<TestSuite>, Diagnostics> {
+    Parser::new().parse_path(path)
+}
+
+/// Parse the script at the given path. Included files are resolved relative to the directory of the including file.
+pub fn parse_path_with_default_settings<P: AsRef<Path>>(path: P, default_test_settings: TestCaseSettings) -> Result<Vec<TestSuite>, Diagnostics> {
+    Parser::new().default_test_settings(default_test_settings).parse_path(path)
+}
+
+/// Set a test option given by name and value as written in scripts, e.g. `timeout` and `500ms`.
+pub fn set_test_option_value(name: &str, value: &str, settings: &mut TestCaseSettings) -> Result<(), Error> {
+    let mut lexer = Lexer::new(format!("{}={}", name, value));
+    let mut tokens = lexer.get_tokens();
+
+    // drop end of file
+    tokens.pop();
+
+    if let Some(token) = tokens.iter().find(|token| token.token_type == TokenType::Illegal || token.token_type == TokenType::Newline) {
+        return Err(Error::IllegalToken(token.value.clone(), token.line, token.column));
+    }
+
+    if tokens.first().is_none_or(|token| token.token_type != TokenType::Identifier) {
+        return Err(Error::MissingOptionIdentifier(1, 1));
+    }
 
-    analyse_tokens(tokens, default_test_settings)
+    let offset = set_test_option(&tokens, settings)?;
+
+    if let Some(token) = tokens.get(offset + 1) {
+        return Err(Error::UnknownError(token.line, token.column));
+    }
+
+    Ok(())
 }
 
-fn analyse_tokens(tokens: Vec<Token>, default_test_settings: TestCaseSettings) -> Result<Vec<TestSuite>, Error> {
-    let mut lines: Vec<Vec<Token>> = Vec::new();
+/// Analyse all lines of a script. Errors are collected and analysis continues with the next line.
+fn analyse_tokens(tokens: Vec<Token>, default_test_settings: &TestCaseSettings, test_suites: &mut Vec<TestSuite>, source: &Source, context: &mut IncludeContext, diagnostics: &mut Vec<Diagnostic>) {
+    let mut lines: Vec<(Vec<Token>, Vec<Error>)> = Vec::new();
     let mut line: Vec<Token> = Vec::new();
+    let mut line_errors: Vec<Error> = Vec::new();
 
-    // split token stream into lines
+    // split token stream into lines, illegal tokens are kept as errors of their line
     for token in tokens {
         if token.token_type == TokenType::Illegal {
-            return Err(Error::IllegalToken(token.value, token.line, token.column));
+            line_errors.push(Error::IllegalToken(token.value, token.line, token.column));
+
+            continue;
+        }
+
+        if token.token_type == TokenType::EndOfFile {
+            break;
         }
 
         if token.token_type == TokenType::Newline {
             // only add line if not empty
-            if line.len() > 0 {
-                lines.push(line);
-
-                line = Vec::new();
+            if !line.is_empty() || !line_errors.is_empty() {
+                lines.push((line, line_errors));
             }
 
+            line = Vec::new();
+            line_errors = Vec::new();
+
             continue;
         }
 
         line.push(token);
     }
 
-    // analyse each line
-    let mut test_suites: Vec<TestSuite> = Vec::new();
+    // last line may not end with a newline
+    if !line.is_empty() || !line_errors.is_empty() {
+        lines.push((line, line_errors));
+    }
 
+    // analyse each line
     // <> mark optional tokens
     // / mark alternative tokens
     // * repeat tokens
-    // [ Identifier <, Identifier < = Value> >* ]
+    // [ Identifier <, Identifier < = Value / <b/o/d/h>" Content "> >* ]
     let group_state_machine = FiniteStateMachine::new(1, vec!(4), |state, token| -> u32 {
         match state {
             1 if token.token_type == TokenType::LeftGroupParenthesis => 2,
@@ -104,23 +321,34 @@ fn analyse_tokens(tokens: Vec<Token>, default_test_settings: TestCaseSettings) -
             3 if token.token_type == TokenType::ContentSeparator => 5,
             5 if token.token_type == TokenType::Identifier => 6,
             6 if token.token_type == TokenType::OptionSeparator => 7,
+            6 if token.token_type == TokenType::ContentSeparator => 5,
+            6 if token.token_type == TokenType::RightGroupParenthesis => 4,
             7 if token.token_type == TokenType::Identifier => 3,
+            7 if token.token_type == TokenType::Content => 3,
+            7 if token.token_type == TokenType::FormatSpecifier => 8,
+            8 if token.token_type == TokenType::Content => 3,
             _ => 0
         }
     });
 
-    // <( Identifier <, Identifier < = Value> >* )> < <b/o/d/h>" Content ">* : < <b/o/d/h>" Content ">*
-    let test_state_machine = FiniteStateMachine::new(1, vec!(9), |state, token| -> u32 {
+    // <( Identifier <, Identifier < = Value> >* )> << <b/o/d/h>" Content ">* : << <b/o/d/h>" Content ">*>
+    // <( Identifier <, Identifier < = Value> >* )> : < <b/o/d/h>" Content ">*
+    // <( Identifier <, Identifier < = Value> >* )> {
+    let test_state_machine = FiniteStateMachine::new(1, vec!(7, 9, 13), |state, token| -> u32 {
         match state {
             1 if token.token_type == TokenType::LeftTestParenthesis => 2,
+            1 if token.token_type == TokenType::LeftBlockParenthesis => 13,
             1 if token.token_type == TokenType::FormatSpecifier => 5,
-            1 if token.token_type == TokenType::Content => 5,
+            1 if token.token_type == TokenType::Content => 6,
+            1 if token.token_type == TokenType::DirectionSeparator => 14,
             2 if token.token_type == TokenType::Identifier => 3,
             2 if token.token_type == TokenType::ContentSeparator => 10,
             3 if token.token_type == TokenType::RightTestParenthesis => 4,
             3 if token.token_type == TokenType::ContentSeparator => 10,
             4 if token.token_type == TokenType::FormatSpecifier => 5,
             4 if token.token_type == TokenType::Content => 6,
+            4 if token.token_type == TokenType::LeftBlockParenthesis => 13,
+            4 if token.token_type == TokenType::DirectionSeparator => 14,
             5 if token.token_type == TokenType::Content => 6,
             6 if token.token_type == TokenType::DirectionSeparator => 7,
             7 if token.token_type == TokenType::FormatSpecifier => 8,
@@ -131,42 +359,283 @@ fn analyse_tokens(tokens: Vec<Token>, default_test_settings: TestCaseSettings) -
             11 if token.token_type == TokenType::ContentSeparator => 10,
             11 if token.token_type == TokenType::RightTestParenthesis => 4,
             12 if token.token_type == TokenType::Identifier => 3,
+            12 if token.token_type == TokenType::Content => 3,
+            12 if token.token_type == TokenType::FormatSpecifier => 15,
+            15 if token.token_type == TokenType::Content => 3,
+            14 if token.token_type == TokenType::FormatSpecifier => 8,
+            14 if token.token_type == TokenType::Content => 9,
             _ => 0
         }
     });
 
-    for line in lines {
+    // test block with position of its opening parenthesis
+    let mut block: Option<(TestCase, Option<TestHook>, u32, u32)> = None;
+
+    for (line, line_errors) in lines {
+        // skip lines with illegal tokens
+        if !line_errors.is_empty() {
+            diagnostics.extend(line_errors.into_iter().map(|err| source.diagnostic(err)));
+
+            continue;
+        }
+
         let first_token: &Token = line.first().unwrap();
 
+        // steps of a test block until the closing parenthesis
+        if block.is_some() {
+            if first_token.token_type == TokenType::RightBlockParenthesis {
+                let (test, hook, line_number, column) = block.take().unwrap();
+
+                if line.len() > 1 {
+                    diagnostics.push(source.diagnostic(Error::UnknownError(line[1].line, line[1].column)));
+                }
+
+                if test.steps().is_empty() {
+                    diagnostics.push(source.diagnostic(Error::MissingContent("step".to_string(), line_number, column)));
+                } else {
+                    push_test(test_suites, test, hook, default_test_settings, &mut context.group);
+                }
+
+                continue;
+            }
+
+            let test = &mut block.as_mut().unwrap().0;
+
+            match analyse_step(&line, &test.settings) {
+                Ok(step) => test.push_step(step),
+                Err(err) => diagnostics.push(source.diagnostic(err))
+            };
+
+            continue;
+        }
+
+        // include "path"
+        if first_token.token_type == TokenType::Identifier && first_token.value.trim() == "include" {
+            if let Err(err) = analyse_include(&line, default_test_settings, test_suites, source, context, diagnostics) {
+                diagnostics.push(source.diagnostic(err));
+            }
+
+            continue;
+        }
+
+        // [@port name]
+        if first_token.token_type == TokenType::LeftGroupParenthesis && line.len() > 1 && line[1].value.starts_with('@') {
+            if let Err(err) = analyse_header(&line, context) {
+                diagnostics.push(source.diagnostic(err));
+            }
+
+            continue;
+        }
+
         if first_token.token_type == TokenType::LeftGroupParenthesis {
-            match analyse_test_group(&line, &group_state_machine, default_test_settings.clone()) {
-                Ok(test_suite) => test_suites.push(test_suite),
-                Err(err) => return Err(err)
+            let result = analyse_test_group(&line, &group_state_machine, default_test_settings.clone())
+                .and_then(|test_suite| check_port(&line, &test_suite.test_settings, context).map(|_| test_suite));
+
+            match result {
+                Ok(test_suite) => {
+                    test_suites.push(test_suite);
+                    context.group = Some(test_suites.len() - 1);
+                },
+                Err(err) => diagnostics.push(source.diagnostic(err))
             };
 
             continue;
         }
 
-        if first_token.token_type == TokenType::LeftTestParenthesis || first_token.token_type == TokenType::FormatSpecifier || first_token.token_type == TokenType::Content {
-            match analyse_test(&line, &test_state_machine) {
-                Ok(test) => {
-                    if test_suites.is_empty() {
-                        test_suites.push(TestSuite::new(String::new()));
-                    }
+        if first_token.token_type == TokenType::LeftTestParenthesis || first_token.token_type == TokenType::LeftBlockParenthesis || first_token.token_type == TokenType::FormatSpecifier || first_token.token_type == TokenType::Content || first_token.token_type == TokenType::DirectionSeparator {
+            let result = analyse_test(&line, &test_state_machine)
+                .and_then(|(test, hook)| check_port(&line, &test.settings, context).map(|_| (test, hook)));
+
+            match result {
+                Ok((test, hook)) => {
+                    let last_token = line.last().unwrap();
 
-                    let test_suite: &mut TestSuite = test_suites.last_mut().unwrap();
-                    test_suite.push(test);
+                    if test.is_sequence() {
+                        block = Some((test, hook, last_token.line, last_token.column));
+                    } else {
+                        push_test(test_suites, test, hook, default_test_settings, &mut context.group);
+                    }
                 }
-                Err(err) => return Err(err)
+                Err(err) => diagnostics.push(source.diagnostic(err))
             };
 
             continue;
         }
 
-        return Err(Error::InvalidLineStart(first_token.line, first_token.column));
+        diagnostics.push(source.diagnostic(Error::InvalidLineStart(first_token.line, first_token.column)));
     }
 
-    Ok(test_suites)
+    if let Some((_, _, line, column)) = block {
+        diagnostics.push(source.diagnostic(Error::MissingClosingParenthesis("}".to_string(), line, column)));
+    }
+}
+
+/// Add a test to the current test suite, tests before the first group are added to an unnamed test suite using the
+/// default test settings.
+fn push_test(test_suites: &mut Vec<TestSuite>, test: TestCase, hook: Option<TestHook>, default_test_settings: &TestCaseSettings, group: &mut Option<usize>) {
+    let index = match *group {
+        Some(index) => index,
+        None => {
+            test_suites.push(TestSuite::new_with_settings(String::new(), Default::default(), default_test_settings.clone()));
+
+            test_suites.len() - 1
+        }
+    };
+
+    *group = Some(index);
+
+    let test_suite: &mut TestSuite = &mut test_suites[index];
+
+    if let Some(hook) = hook {
+        test_suite.push_hook(hook, test);
+    } else {
+        test_suite.push(test);
+    }
+}
+
+/// Add groups and tests of the included file as if they were written in place of the include line. Tests following
+/// the include line are added to the group of the include line again.
+///
+/// Errors in the included file are added to the diagnostics, errors of the include line itself are returned.
+fn analyse_include(tokens: &[Token], default_test_settings: &TestCaseSettings, test_suites: &mut Vec<TestSuite>, source: &Source, context: &mut IncludeContext, diagnostics: &mut Vec<Diagnostic>) -> Result<(), Error> {
+    if tokens.len() < 2 || tokens[1].token_type != TokenType::Content {
+        return Err(Error::MissingIncludePath(tokens[0].line, tokens[0].column));
+    }
+
+    if tokens.len() > 2 {
+        return Err(Error::UnknownError(tokens[2].line, tokens[2].column));
+    }
+
+    let name = tokens[1].value.clone();
+    let line = tokens[1].line;
+    let column = tokens[1].column;
+
+    let path = match fs::canonicalize(context.base_dir.join(&name)) {
+        Ok(path) => path,
+        Err(_) => return Err(Error::IncludeFileError(name, line, column))
+    };
+
+    if context.files.contains(&path) {
+        return Err(Error::IncludeCycle(name, line, column));
+    }
+
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(_) => return Err(Error::IncludeFileError(name, line, column))
+    };
+
+    // show included files relative to the including file
+    let file_name = match source.name {
+        Some(ref source_name) => Path::new(source_name).parent().map_or(PathBuf::from(&name), |dir| dir.join(&name)).to_string_lossy().to_string(),
+        None => name
+    };
+
+    let included_source = Source::new(Some(file_name), &content);
+
+    let mut lexer = Lexer::new(content);
+    let included_tokens = lexer.get_tokens();
+
+    let base_dir = path.parent().map_or(PathBuf::from("."), |dir| dir.to_path_buf());
+    let parent_base_dir = std::mem::replace(&mut context.base_dir, base_dir);
+    let parent_group = context.group;
+    let first_suite = test_suites.len();
+    context.files.push(path);
+
+    analyse_tokens(included_tokens, default_test_settings, test_suites, &included_source, context, diagnostics);
+
+    context.files.pop();
+    context.base_dir = parent_base_dir;
+
+    // ungrouped tests at the start of the included file are ungrouped in the including file as well
+    context.group = match parent_group {
+        Some(index) => Some(index),
+        None if test_suites.get(first_suite).is_some_and(|test_suite| test_suite.name.is_empty()) => Some(first_suite),
+        None => None
+    };
+
+    Ok(())
+}
+
+/// [ @port Identifier? (, Identifier = Value)* ]
+fn analyse_header(tokens: &[Token], context: &mut IncludeContext) -> Result<(), Error> {
+    let header = &tokens[1];
+    let keyword = header.value.trim();
+
+    if keyword != "@port" {
+        return Err(Error::UnknownHeader(keyword.to_string(), header.line, header.column));
+    }
+
+    let mut index = 2;
+    let mut name = None;
+
+    // the port name is an identifier not followed by an option separator
+    if let Some(token) = tokens.get(index) {
+        let is_option = tokens.get(index + 1).is_some_and(|next| next.token_type == TokenType::OptionSeparator);
+
+        if token.token_type == TokenType::Identifier && is_option == false {
+            let value = token.value.trim();
+
+            if value.contains(char::is_whitespace) || value.contains('@') {
+                return Err(Error::InvalidPortName(value.to_string(), token.line, token.column));
+            }
+
+            name = Some(value.to_string());
+            index += 1;
+        }
+    }
+
+    let mut settings = PartialSettings::default();
+
+    loop {
+        let token = match tokens.get(index) {
+            Some(token) => token,
+            None => return Err(Error::MissingClosingParenthesis("]".to_string(), header.line, header.column))
+        };
+
+        match token.token_type {
+            TokenType::RightGroupParenthesis => break,
+            TokenType::ContentSeparator => index += 1,
+            TokenType::Identifier => index += set_serial_option(&tokens[index..], &mut settings)? + 1,
+            _ => return Err(Error::UnknownError(token.line, token.column))
+        };
+    }
+
+    if tokens.len() > index + 1 {
+        return Err(Error::UnknownError(tokens[index + 1].line, tokens[index + 1].column));
+    }
+
+    let name = match name {
+        Some(name) => name,
+        None if settings.is_empty() => return Err(Error::MissingPortName(header.line, header.column)),
+        None => {
+            context.settings.merge(&settings);
+
+            return Ok(());
+        }
+    };
+
+    // ports may be declared again, e.g. by included files
+    match context.ports.iter_mut().find(|port| port.name == name) {
+        Some(port) => port.settings.merge(&settings),
+        None => context.ports.push(PortDeclaration { name, settings })
+    };
+
+    Ok(())
+}
+
+/// Check the port set by the options of the line has been declared.
+fn check_port(tokens: &[Token], settings: &TestCaseSettings, context: &IncludeContext) -> Result<(), Error> {
+    let name = match settings.port {
+        Some(ref name) if context.ports.iter().all(|port| port.name != *name) => name,
+        _ => return Ok(())
+    };
+
+    // report the error at the option value
+    let token = tokens.windows(3)
+        .find(|option| option[0].value.trim() == "port" && option[1].token_type == TokenType::OptionSeparator)
+        .map_or(&tokens[0], |option| &option[2]);
+
+    Err(Error::UnknownPort(name.clone(), token.line, token.column))
 }
 
 fn analyse_test_group(tokens: &Vec<Token>, state_machine: &FiniteStateMachine, default_test_settings: TestCaseSettings) -> Result<TestSuite, Error> {
@@ -178,7 +647,7 @@ fn analyse_test_group(tokens: &Vec<Token>, state_machine: &FiniteStateMachine, d
             3 => Err(Error::MissingClosingParenthesis("]".to_string(), token.line, token.column)),
             5 => Err(Error::MissingOptionIdentifier(token.line, token.column)),
             6 => Err(Error::MissingOptionSeparator(token.line, token.column)),
-            7 => Err(Error::MissingOptionValue(token.line, token.column)),
+            7 | 8 => Err(Error::MissingOptionValue(token.line, token.column)),
             _ => Err(Error::UnknownError(token.line, token.column))
         };
     }
@@ -203,7 +672,7 @@ fn analyse_test_group(tokens: &Vec<Token>, state_machine: &FiniteStateMachine, d
     Ok(test_suite)
 }
 
-fn analyse_test(tokens: &Vec<Token>, state_machine: &FiniteStateMachine) -> Result<TestCase, Error> {
+fn analyse_test(tokens: &Vec<Token>, state_machine: &FiniteStateMachine) -> Result<(TestCase, Option<TestHook>), Error> {
     let result = state_machine.run(&tokens);
 
     if let Err((state, token)) = result {
@@ -212,21 +681,22 @@ fn analyse_test(tokens: &Vec<Token>, state_machine: &FiniteStateMachine) -> Resu
             3 => Err(Error::MissingClosingParenthesis(")".to_string(), token.line, token.column)),
             4 | 5 => Err(Error::MissingContent("input".to_string(), token.line, token.column)),
             6 => Err(Error::MissingDirectionSeparator(token.line, token.column)),
-            7 | 8 => Err(Error::MissingContent("output".to_string(), token.line, token.column)),
+            7 | 8 | 14 => Err(Error::MissingContent("output".to_string(), token.line, token.column)),
             10 => Err(Error::MissingOptionIdentifier(token.line, token.column)),
             11 => Err(Error::MissingOptionSeparator(token.line, token.column)),
-            12 => Err(Error::MissingOptionValue(token.line, token.column)),
+            12 | 15 => Err(Error::MissingOptionValue(token.line, token.column)),
             _ => Err(Error::UnknownError(token.line, token.column))
         };
     }
 
     // create test case
     let mut name = String::new();
-    let input: String;
-    let output: String;
+    let mut input: Option<String> = None;
+    let mut output: Option<String> = None;
     let mut settings = TestCaseSettings::default();
     let mut input_format: Option<TextFormat> = None;
     let mut output_format: Option<TextFormat> = None;
+    let mut hook: Option<TestHook> = None;
 
     let mut index = 0;
 
@@ -238,30 +708,48 @@ fn analyse_test(tokens: &Vec<Token>, state_machine: &FiniteStateMachine) -> Resu
 
         index += 1;
 
-        index += analyse_test_options(&tokens[index..], &mut settings)?;
+        index += analyse_test_options(&tokens[index..], &mut settings, &mut hook)?;
     }
 
-    if tokens[index].token_type == TokenType::FormatSpecifier {
-        input_format = Some(get_text_format(&tokens[index])?);
-        index += 1;
-    }
+    // steps follow in a block
+    if tokens[index].token_type == TokenType::LeftBlockParenthesis {
+        let mut test = TestCase::new_sequence(name, Vec::new());
+        test.settings = settings;
 
-    input = tokens[index].value.clone();
+        return Ok((test, hook));
+    }
 
-    // skip direction separator
-    index += 2;
+    // input is missing for tests only expecting output
+    if tokens[index].token_type != TokenType::DirectionSeparator {
+        if tokens[index].token_type == TokenType::FormatSpecifier {
+            input_format = Some(get_text_format(&tokens[index])?);
+            index += 1;
+        }
 
-    if tokens[index].token_type == TokenType::FormatSpecifier {
-        output_format = Some(get_text_format(&tokens[index])?);
+        input = Some(tokens[index].value.clone());
         index += 1;
     }
 
-    output = tokens[index].value.clone();
-    if let Err(_) = Regex::new(&output) {
-        return Err(Error::InvalidOutputContent(output, tokens[index].line, tokens[index].column));
+    // skip direction separator
+    index += 1;
+
+    // output is missing for tests only sending input
+    if index < tokens.len() {
+        if tokens[index].token_type == TokenType::FormatSpecifier {
+            output_format = Some(get_text_format(&tokens[index])?);
+            index += 1;
+        }
+
+        output = Some(tokens[index].value.clone());
+        validate_output(&tokens[index], output_format.as_ref().unwrap_or(&TextFormat::Text), &settings)?;
     }
 
-    let mut test = TestCase::new(name, input, output);
+    let mut test = match (input, output) {
+        (Some(input), Some(output)) => TestCase::new(name, input, output),
+        (Some(input), None) => TestCase::new_send(name, input),
+        (None, Some(output)) => TestCase::new_expect(name, output),
+        (None, None) => return Err(Error::MissingContent("output".to_string(), tokens[index - 1].line, tokens[index - 1].column))
+    };
     test.settings = settings;
 
     if let Some(format) = input_format {
@@ -272,10 +760,71 @@ fn analyse_test(tokens: &Vec<Token>, state_machine: &FiniteStateMachine) -> Resu
         test.output_format = format;
     }
 
-    Ok(test)
+    Ok((test, hook))
 }
 
-fn analyse_test_options(tokens: &[Token], settings: &mut TestCaseSettings) -> Result<usize, Error> {
+/// send <b/o/d/h>" Content " or expect <b/o/d/h>" Content "
+fn analyse_step(tokens: &[Token], settings: &TestCaseSettings) -> Result<TestStep, Error> {
+    let keyword = tokens[0].value.trim().to_string();
+
+    if tokens[0].token_type != TokenType::Identifier || (keyword != "send" && keyword != "expect") {
+        return Err(Error::InvalidStep(keyword, tokens[0].line, tokens[0].column));
+    }
+
+    let content_type = if keyword == "send" { "input" } else { "output" };
+
+    let mut index = 1;
+    let mut format = TextFormat::Text;
+
+    if index < tokens.len() && tokens[index].token_type == TokenType::FormatSpecifier {
+        format = get_text_format(&tokens[index])?;
+        index += 1;
+    }
+
+    if index >= tokens.len() || tokens[index].token_type != TokenType::Content {
+        let token = tokens.get(index).unwrap_or(&tokens[index - 1]);
+
+        return Err(Error::MissingContent(content_type.to_string(), token.line, token.column));
+    }
+
+    if index + 1 < tokens.len() {
+        return Err(Error::UnknownError(tokens[index + 1].line, tokens[index + 1].column));
+    }
+
+    let content = tokens[index].value.clone();
+
+    if keyword == "send" {
+        return Ok(TestStep::Send(content, format));
+    }
+
+    validate_output(&tokens[index], &format, settings)?;
+
+    Ok(TestStep::Expect(content, format))
+}
+
+/// Check the output content is a valid regex, outputs matched literally are not checked.
+fn validate_output(token: &Token, format: &TextFormat, settings: &TestCaseSettings) -> Result<(), Error> {
+    // variables are resolved when running the test
+    let pattern = match variables::interpolate(&token.value, |_| Ok(String::new())) {
+        Ok(pattern) => pattern,
+        Err(_) => return Err(Error::InvalidOutputContent(token.value.clone(), token.line, token.column))
+    };
+
+    // outputs written as bytes are compiled into a regex in every match mode
+    let pattern = match byte_pattern::compile(&pattern, format) {
+        Some(bytes) => bytes,
+        None if settings.match_mode.is_none_or(|match_mode| match_mode.is_regex()) => pattern,
+        None => return Ok(())
+    };
+
+    if Regex::new(&pattern).is_err() {
+        return Err(Error::InvalidOutputContent(token.value.clone(), token.line, token.column));
+    }
+
+    Ok(())
+}
+
+fn analyse_test_options(tokens: &[Token], settings: &mut TestCaseSettings, hook: &mut Option<TestHook>) -> Result<usize, Error> {
     let mut index = 0;
 
     while tokens[index].token_type == TokenType::ContentSeparator {
@@ -285,7 +834,16 @@ fn analyse_test_options(tokens: &[Token], settings: &mut TestCaseSettings) -> Re
             option_length += 1;
         }
 
-        let offset = set_test_option(&tokens[index + 1 .. index + option_length], settings)?;
+        // test for both test and hook option
+        let offset = match set_test_option(&tokens[index + 1 .. index + option_length], settings) {
+            Ok(offset) => offset,
+            Err(err) => {
+                match err {
+                    Error::UnknownTestOption(_, _, _) => set_hook_option(&tokens[index + 1 .. index + option_length], hook)?,
+                    _ => return Err(err)
+                }
+            },
+        };
 
         index += 2 + offset;
     }
@@ -329,3 +887,152 @@ fn get_text_format(token: &Token) -> Result<TextFormat, Error> {
         _ => Err(Error::UnknownError(token.line, token.column))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs;
+    use super::error::Error;
+    use crate::tests::Comparison;
+    use crate::serial::settings::{Parity, StopBits};
+
+    #[test]
+    fn parse_included_files() {
+        let dir = env::temp_dir().join(format!("sut-include-{}", std::process::id()));
+        fs::create_dir_all(dir.join("common")).unwrap();
+
+        fs::write(dir.join("main.sut"), "(First)\"a\" : \"a\"\ninclude \"common/group.sut\"\n(Last)\"c\" : \"c\"\n").unwrap();
+        fs::write(dir.join("common/group.sut"), "[Group]\n(Second)\"b\" : \"b\"\n").unwrap();
+
+        let test_suites = super::parse_path(dir.join("main.sut")).unwrap();
+
+        assert_eq!(test_suites.len(), 2);
+        assert_eq!(test_suites[1].name, "Group");
+        assert_eq!(test_suites[1].len(), 1);
+
+        // tests after the include line belong to the group of the include line
+        let names: Vec<&str> = test_suites[0].tests().iter().map(|test| test.name()).collect();
+
+        assert_eq!(names, vec!("First", "Last"));
+
+        fs::write(dir.join("main.sut"), "[Outer]\n(First)\"a\" : \"a\"\ninclude \"common/group.sut\"\n(Last)\"c\" : \"c\"\n").unwrap();
+        fs::write(dir.join("common/group.sut"), "(Included)\"x\" : \"x\"\n[Group]\n(Second)\"b\" : \"b\"\n").unwrap();
+
+        let test_suites = super::parse_path(dir.join("main.sut")).unwrap();
+        let names: Vec<&str> = test_suites[0].tests().iter().map(|test| test.name()).collect();
+
+        assert_eq!(test_suites[0].name, "Outer");
+        assert_eq!(names, vec!("First", "Included", "Last"));
+        assert_eq!(test_suites[1].name, "Group");
+        assert_eq!(test_suites[1].len(), 1);
+
+        fs::write(dir.join("common/group.sut"), "include \"../main.sut\"\n").unwrap();
+
+        let diagnostics = super::parse_path(dir.join("main.sut")).unwrap_err();
+        let diagnostic = diagnostics.iter().next().unwrap();
+
+        assert_eq!(diagnostic.error, Error::IncludeCycle("../main.sut".to_string(), 1, 9));
+        assert_eq!(diagnostic.file, Some("common/group.sut".to_string()));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn parse_string_without_trailing_newline() {
+        let test_suites = super::parse_str("[Group]\n(First)\"a\" : \"a\"\n(Second)\"b\" : \"b\"").unwrap();
+
+        assert_eq!(test_suites[0].len(), 2);
+
+        let test_suites = super::parse_reader("(First)\"a\" : \"a\"\n# comment".as_bytes()).unwrap();
+
+        assert_eq!(test_suites[0].len(), 1);
+
+        let err = super::Parser::new().source_name("generated").parse_str("(First)\"a\"").unwrap_err();
+
+        assert_eq!(err.to_string(), "Missing direction separator at 1:8 in 'generated'");
+    }
+
+    #[test]
+    fn parse_assertions() {
+        let test_suites = super::parse_str("(Temperature, assert=\"t == 21.5±0.5\")\"temp\" : \"(?P<t>[0-9.]+)°C\"\n").unwrap();
+        let test = &test_suites[0].tests()[0];
+
+        assert_eq!(test.settings.assertions[0].comparison, Comparison::Equal(21.5, 0.5));
+        assert_eq!(test.output(), "(?P<t>[0-9.]+)°C");
+
+        let diagnostics = super::parse_str("(Temperature, assert=\"t = 21.5\")\"temp\" : \"21.5\"\n").unwrap_err();
+
+        assert_eq!(diagnostics.iter().next().unwrap().error, Error::InvalidAssertion("Missing comparison after 't'".to_string(), 1, 22));
+    }
+
+    #[test]
+    fn parse_byte_patterns() {
+        let test_suites = super::parse_str("(Any)h\"01\" : h\"?? AA\"\n(Nibble)h\"01\" : h\"?A 01\"\n(Skip)h\"01\" : h\"..{4}\"\n").unwrap();
+        let outputs: Vec<&str> = test_suites[0].tests().iter().map(|test| test.output()).collect();
+
+        assert_eq!(outputs, vec!("?? AA", "?A 01", "..{4}"));
+
+        let diagnostics = super::parse_str("(Broken)h\"01\" : h\"AA(\"\n").unwrap_err();
+
+        assert_eq!(diagnostics.iter().next().unwrap().error, Error::InvalidOutputContent("AA(".to_string(), 1, 18));
+    }
+
+    #[test]
+    fn parse_port_declarations() {
+        let content = "[@port gateway]\n[@port node]\n[Pairing, port=gateway]\n\"pair\" :\n(Joined, port=node) : \"joined\"\n";
+        let script = super::Parser::new().parse_script_str(content).unwrap();
+
+        let names: Vec<&str> = script.ports.iter().map(|port| port.name.as_str()).collect();
+
+        assert_eq!(names, vec!("gateway", "node"));
+        assert_eq!(script.test_suites[0].tests()[0].settings.port, Some("gateway".to_string()));
+        assert_eq!(script.test_suites[0].tests()[1].settings.port, Some("node".to_string()));
+        assert!(!script.uses_default_port());
+
+        let diagnostics = super::parse_str("[@port gateway]\n(Joined, port=nod) : \"joined\"\n[@prot node]\n").unwrap_err();
+        let errors: Vec<&Error> = diagnostics.iter().map(|diagnostic| &diagnostic.error).collect();
+
+        assert_eq!(errors, vec!(
+            &Error::UnknownPort("nod".to_string(), 2, 15),
+            &Error::UnknownHeader("@prot".to_string(), 3, 2)
+        ));
+    }
+
+    #[test]
+    fn parse_port_settings() {
+        let content = "[@port baud=115200, parity=even, timeout=500ms]\n[@port node, stop-bits=2]\n[Fast, baud=57600]\n\"a\" : \"a\"\n";
+        let script = super::Parser::new().parse_script_str(content).unwrap();
+
+        assert_eq!(script.settings.baud_rate, Some(115200));
+        assert_eq!(script.settings.parity, Some(Parity::Even));
+        assert_eq!(script.settings.timeout, Some(500));
+        assert_eq!(script.port("node").unwrap().settings.stop_bits, Some(StopBits::Two));
+        assert_eq!(script.test_suites[0].settings.serial.baud_rate, Some(57600));
+
+        let diagnostics = super::parse_str("[@port bud=9600]\n[@port parity=space]\n").unwrap_err();
+        let errors: Vec<&Error> = diagnostics.iter().map(|diagnostic| &diagnostic.error).collect();
+
+        assert_eq!(errors, vec!(
+            &Error::UnknownPortOption("bud".to_string(), 1, 8),
+            &Error::InvalidOptionValue("parity".to_string(), 2, 15)
+        ));
+    }
+
+    #[test]
+    fn collect_all_errors() {
+        let content = "[Group, timout=1s]\n(First)\"a\" : \"a\"\n(Second)\"b\n(Third)\"c\" \"c\"\n";
+        let diagnostics = super::parse_str(content).unwrap_err();
+
+        let errors: Vec<&Error> = diagnostics.iter().map(|diagnostic| &diagnostic.error).collect();
+
+        assert_eq!(errors, vec!(
+            &Error::UnknownGroupOption("timout".to_string(), 1, 9),
+            &Error::IllegalToken("\"b".to_string(), 3, 9),
+            &Error::MissingDirectionSeparator(4, 12)
+        ));
+
+        let hint = diagnostics.iter().next().unwrap().hint.clone();
+
+        assert_eq!(hint, Some("unknown option 'timout', did you mean 'timeout'?".to_string()));
+    }
+}
@@ -26,11 +26,28 @@
  * SOFTWARE.
  */
 
-use crate::tests::{TestCaseSettings, TestSuiteSettings};
-use super::token::{Token};
+use crate::serial::Framing;
+use crate::serial::settings::PartialSettings;
+use crate::tests::{TestCaseSettings, TestSuiteSettings, TestHook, Assertion};
+use crate::tests::test_case::content_bytes;
+use crate::utils::TextFormat;
+use super::token::{Token, TokenType};
+use super::get_text_format;
 use super::string_util;
 use super::error::Error;
 
+/// Names of all test options, also valid as group options.
+pub const TEST_OPTIONS: &[&str] = &["ignore-case", "allow-failure", "delay", "timeout", "repeat", "match", "terminator", "length", "idle", "assert", "port"];
+
+/// Names of all options marking a test as hook of its group.
+pub const HOOK_OPTIONS: &[&str] = &["setup", "teardown", "setup-each", "teardown-each"];
+
+/// Names of all options only valid for groups.
+pub const GROUP_OPTIONS: &[&str] = &["stop-on-failure", "baud", "data-bits", "parity", "stop-bits", "flow-control"];
+
+/// Names of all serial settings of port headers, also valid as group options except for the timeout.
+pub const SERIAL_OPTIONS: &[&str] = &["baud", "data-bits", "parity", "stop-bits", "flow-control", "timeout"];
+
 pub fn set_test_option(tokens: &[Token], settings: &mut TestCaseSettings) -> Result<usize, Error> {
     let name = tokens[0].value.trim();
 
@@ -42,6 +59,11 @@ pub fn set_test_option(tokens: &[Token], settings: &mut TestCaseSettings) -> Res
     };
 
     // options with explicit value
+    match name {
+        "delay" | "timeout" | "repeat" | "match" | "terminator" | "length" | "idle" | "assert" | "port" => (),
+        _ => return Err(Error::UnknownTestOption(name.to_string(), tokens[0].line, tokens[0].column))
+    };
+
     if tokens.len() < 3 {
         return Err(Error::MissingOptionValue(tokens[0].line, tokens[0].column));
     }
@@ -76,6 +98,70 @@ pub fn set_test_option(tokens: &[Token], settings: &mut TestCaseSettings) -> Res
                 Err(Error::InvalidOptionValue("number".to_string(), tokens[2].line, tokens[2].column))
             }
         },
+        "match" => {
+            if let Some(match_mode) = string_util::get_match_mode(value.trim()) {
+                settings.match_mode = Some(match_mode);
+
+                Ok(2)
+            } else {
+                Err(Error::InvalidOptionValue("match mode".to_string(), tokens[2].line, tokens[2].column))
+            }
+        },
+        "terminator" => {
+            // terminator content can have a format specifier
+            let (format, content, offset) = if tokens[2].token_type == TokenType::FormatSpecifier && tokens.len() >= 4 {
+                (get_text_format(&tokens[2])?, &tokens[3], 3)
+            } else {
+                (TextFormat::Text, &tokens[2], 2)
+            };
+
+            match content_bytes(&content.value, &format) {
+                Ok(ref bytes) if bytes.is_empty() == false => {
+                    settings.framing = Some(Framing::Terminator(bytes.clone()));
+
+                    Ok(offset)
+                },
+                _ => Err(Error::InvalidOptionValue("terminator".to_string(), content.line, content.column))
+            }
+        },
+        "length" => {
+            match value.parse::<usize>() {
+                Ok(length) if length > 0 => {
+                    settings.framing = Some(Framing::Length(length));
+
+                    Ok(2)
+                },
+                _ => Err(Error::InvalidOptionValue("number".to_string(), tokens[2].line, tokens[2].column))
+            }
+        },
+        "assert" => {
+            match Assertion::parse(&value) {
+                Ok(assertion) => {
+                    settings.assertions.push(assertion);
+
+                    Ok(2)
+                },
+                Err(message) => Err(Error::InvalidAssertion(message, tokens[2].line, tokens[2].column))
+            }
+        },
+        "idle" => {
+            if let Some(time) = string_util::get_time_value(&value) {
+                settings.framing = Some(Framing::Idle(time));
+
+                Ok(2)
+            } else {
+                Err(Error::InvalidOptionValue("time".to_string(), tokens[2].line, tokens[2].column))
+            }
+        },
+        "port" => {
+            if tokens[2].token_type == TokenType::Identifier && value.trim().contains(char::is_whitespace) == false {
+                settings.port = Some(value.trim().to_string());
+
+                Ok(2)
+            } else {
+                Err(Error::InvalidOptionValue("port name".to_string(), tokens[2].line, tokens[2].column))
+            }
+        },
         _ => Err(Error::UnknownTestOption(name.to_string(), tokens[0].line, tokens[0].column))
     }
 }
@@ -89,12 +175,65 @@ pub fn set_group_option(tokens: &[Token], settings: &mut TestSuiteSettings) -> R
         _ => ()
     };
 
-    // options with explicit value
+    // the port is reconfigured while the group runs, timeouts are test options
+    match set_serial_option(tokens, &mut settings.serial) {
+        Err(Error::UnknownPortOption(_, _, _)) => Err(Error::UnknownGroupOption(name.to_string(), tokens[0].line, tokens[0].column)),
+        result => result
+    }
+}
+
+pub fn set_serial_option(tokens: &[Token], settings: &mut PartialSettings) -> Result<usize, Error> {
+    let name = tokens[0].value.trim();
+
+    if SERIAL_OPTIONS.contains(&name) == false {
+        return Err(Error::UnknownPortOption(name.to_string(), tokens[0].line, tokens[0].column));
+    }
+
     if tokens.len() < 3 {
         return Err(Error::MissingOptionValue(tokens[0].line, tokens[0].column));
     }
 
-    Err(Error::UnknownGroupOption(name.to_string(), tokens[0].line, tokens[0].column))
+    let value = tokens[2].value.trim();
+    let invalid = |expected_type: &str| Error::InvalidOptionValue(expected_type.to_string(), tokens[2].line, tokens[2].column);
+
+    match name {
+        "baud" => settings.baud_rate = Some(value.parse::<u32>().map_err(|_| invalid("number"))?),
+        "data-bits" => settings.data_bits = Some(value.parse().map_err(|_| invalid("data bits"))?),
+        "parity" => settings.parity = Some(value.parse().map_err(|_| invalid("parity"))?),
+        "stop-bits" => settings.stop_bits = Some(value.parse().map_err(|_| invalid("stop bits"))?),
+        "flow-control" => settings.flow_control = Some(value.parse().map_err(|_| invalid("flow control"))?),
+        _ => {
+            let timeout = string_util::get_time_value(value).ok_or_else(|| invalid("time"))?;
+
+            settings.timeout = Some(timeout.as_millis() as u64);
+        }
+    };
+
+    Ok(2)
+}
+
+pub fn set_hook_option(tokens: &[Token], hook: &mut Option<TestHook>) -> Result<usize, Error> {
+    let name = tokens[0].value.trim();
+
+    let kind = match name {
+        "setup" => TestHook::Setup,
+        "teardown" => TestHook::Teardown,
+        "setup-each" => TestHook::SetupEach,
+        "teardown-each" => TestHook::TeardownEach,
+        _ => return Err(Error::UnknownTestOption(name.to_string(), tokens[0].line, tokens[0].column))
+    };
+
+    // options with implicit value
+    let mut enabled = false;
+    let offset = parse_boolean_option(tokens, &mut enabled)?;
+
+    if enabled {
+        *hook = Some(kind);
+    } else if *hook == Some(kind) {
+        *hook = None;
+    }
+
+    Ok(offset)
 }
 
 fn parse_boolean_option(tokens: &[Token], option: &mut bool) -> Result<usize, Error> {
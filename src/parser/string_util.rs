@@ -27,6 +27,7 @@
  */
 
 use std::time::Duration;
+use crate::tests::MatchMode;
 
 pub fn get_boolean_value(value: &str) -> Option<bool> {
     match value {
@@ -36,6 +37,18 @@ pub fn get_boolean_value(value: &str) -> Option<bool> {
     }
 }
 
+pub fn get_match_mode(value: &str) -> Option<MatchMode> {
+    match value {
+        "exact" => Some(MatchMode::Exact),
+        "contains" => Some(MatchMode::Contains),
+        "starts-with" => Some(MatchMode::StartsWith),
+        "ends-with" => Some(MatchMode::EndsWith),
+        "line" => Some(MatchMode::Line),
+        "regex-search" => Some(MatchMode::RegexSearch),
+        _ => None
+    }
+}
+
 pub fn get_time_value(value: &str) -> Option<Duration> {
     let mut time_string = String::new();
     let mut unit_string = String::new();
@@ -67,3 +80,23 @@ pub fn get_time_value(value: &str) -> Option<Duration> {
         None
     }
 }
+
+/// Get the number of single character edits needed to change one string into the other.
+pub fn edit_distance(first: &str, second: &str) -> usize {
+    let second: Vec<char> = second.chars().collect();
+    let mut distances: Vec<usize> = (0..=second.len()).collect();
+
+    for (i, first_ch) in first.chars().enumerate() {
+        let mut previous = distances[0];
+        distances[0] = i + 1;
+
+        for (j, second_ch) in second.iter().enumerate() {
+            let substitution = if first_ch == *second_ch { previous } else { previous + 1 };
+
+            previous = distances[j + 1];
+            distances[j + 1] = substitution.min(distances[j] + 1).min(previous + 1);
+        }
+    }
+
+    distances[second.len()]
+}
@@ -43,7 +43,9 @@ pub enum TokenType {
     LeftGroupParenthesis,
     RightGroupParenthesis,
     LeftTestParenthesis,
-    RightTestParenthesis
+    RightTestParenthesis,
+    LeftBlockParenthesis,
+    RightBlockParenthesis
 }
 
 #[derive(Debug)]
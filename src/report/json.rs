@@ -0,0 +1,203 @@
+/*
+ * File: src/report/json.rs
+ * Date: 15.10.2026
+ * Author: agent
+ * 
+ * MIT License
+ * 
+ * Copyright (c) 2026 MarkAtk
+ * 
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+ * of the Software, and to permit persons to whom the Software is furnished to do
+ * so, subject to the following conditions:
+ * 
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ * 
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+use std::io;
+use serde::Serialize;
+use crate::tests::{SuiteResult, TestResult, TestStatus};
+
+#[derive(Serialize)]
+struct Summary {
+    total: usize,
+    passed: usize,
+    failed: usize,
+    errored: usize,
+    allowed_failures: usize,
+    skipped: usize
+}
+
+#[derive(Serialize)]
+struct Report<'a> {
+    suites: &'a [SuiteResult],
+    summary: Summary
+}
+
+#[derive(Serialize)]
+struct PortReport<'a> {
+    port: &'a str,
+    suites: &'a [SuiteResult],
+    summary: Summary
+}
+
+#[derive(Serialize)]
+struct PortsReport<'a> {
+    ports: Vec<PortReport<'a>>,
+    summary: Summary
+}
+
+#[derive(Serialize)]
+struct Line<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    port: Option<&'a str>,
+    suite: &'a str,
+    #[serde(flatten)]
+    test: &'a TestResult
+}
+
+/// Write all test suite results as one JSON document.
+pub fn write<W: io::Write>(writer: &mut W, results: &[SuiteResult]) -> Result<(), io::Error> {
+    let report = Report {
+        suites: results,
+        summary: summary(results.iter())
+    };
+
+    serde_json::to_writer_pretty(&mut *writer, &report)?;
+    writeln!(writer)
+}
+
+/// Write the results of all ports as one JSON document, with a summary for each port and all ports combined.
+pub fn write_ports<W: io::Write>(writer: &mut W, results: &[(String, Vec<SuiteResult>)]) -> Result<(), io::Error> {
+    let report = PortsReport {
+        ports: results.iter().map(|(port, suites)| PortReport {
+            port,
+            suites,
+            summary: summary(suites.iter())
+        }).collect(),
+        summary: summary(results.iter().flat_map(|(_, suites)| suites.iter()))
+    };
+
+    serde_json::to_writer_pretty(&mut *writer, &report)?;
+    writeln!(writer)
+}
+
+/// Write a single test result as one line of JSON, for streaming results while tests are running.
+pub fn write_line<W: io::Write>(writer: &mut W, suite_name: &str, result: &TestResult) -> Result<(), io::Error> {
+    write_port_line(writer, None, suite_name, result)
+}
+
+/// Write a single test result as one line of JSON including the port the test ran on.
+pub fn write_port_line<W: io::Write>(writer: &mut W, port: Option<&str>, suite_name: &str, result: &TestResult) -> Result<(), io::Error> {
+    let line = Line {
+        port,
+        suite: suite_name,
+        test: result
+    };
+
+    serde_json::to_writer(&mut *writer, &line)?;
+    writeln!(writer)?;
+    writer.flush()
+}
+
+fn summary<'a, I: Iterator<Item = &'a SuiteResult> + Clone>(results: I) -> Summary {
+    let count = |status| results.clone().map(|result| result.count(status)).sum();
+
+    Summary {
+        total: results.clone().map(|result| result.tests.len()).sum(),
+        passed: count(TestStatus::Passed),
+        failed: count(TestStatus::Failed),
+        errored: count(TestStatus::Errored),
+        allowed_failures: count(TestStatus::AllowedFailure),
+        skipped: count(TestStatus::Skipped)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+    use crate::serial::transport::MockTransport;
+    use crate::tests::{TestCase, TestSuite, SuiteResult};
+
+    fn run_suite() -> SuiteResult {
+        let mut ports = MockTransport::ports(&[("a", "ok"), ("b", "no")]);
+
+        let mut test_suite = TestSuite::new("Group".to_string());
+        test_suite.push(TestCase::new("First".to_string(), "a".to_string(), "ok".to_string()));
+        test_suite.push(TestCase::new("Second".to_string(), "b".to_string(), "ok".to_string()));
+
+        test_suite.run(&mut ports)
+    }
+
+    #[test]
+    fn write_report() {
+        let mut report = Vec::new();
+        super::write(&mut report, &[run_suite()]).unwrap();
+
+        let report: Value = serde_json::from_slice(&report).unwrap();
+        let test = &report["suites"][0]["tests"][1];
+
+        assert_eq!(report["suites"][0]["name"], "Group");
+        assert_eq!(test["name"], "Second");
+        assert_eq!(test["input"], "b");
+        assert_eq!(test["expected"], "ok");
+        assert_eq!(test["response"], "no");
+        assert_eq!(test["status"], "failed");
+        assert_eq!(test["timed_out"], false);
+        assert!(test["duration"].is_f64());
+        assert_eq!(report["summary"], serde_json::json!({
+            "total": 2, "passed": 1, "failed": 1, "errored": 0, "allowed_failures": 0, "skipped": 0
+        }));
+    }
+
+    #[test]
+    fn write_ports_report() {
+        let results = vec!(("gateway".to_string(), vec!(run_suite())), ("node".to_string(), vec!(run_suite())));
+
+        let mut report = Vec::new();
+        super::write_ports(&mut report, &results).unwrap();
+
+        let report: Value = serde_json::from_slice(&report).unwrap();
+
+        assert_eq!(report["ports"][0]["port"], "gateway");
+        assert_eq!(report["ports"][1]["port"], "node");
+        assert_eq!(report["ports"][1]["suites"][0]["tests"][0]["status"], "passed");
+        assert_eq!(report["ports"][1]["summary"]["total"], 2);
+        assert_eq!(report["summary"]["total"], 4);
+        assert_eq!(report["summary"]["failed"], 2);
+    }
+
+    #[test]
+    fn write_lines() {
+        let result = run_suite();
+
+        let mut lines = Vec::new();
+        super::write_line(&mut lines, &result.name, &result.tests[0]).unwrap();
+        super::write_port_line(&mut lines, Some("node"), &result.name, &result.tests[1]).unwrap();
+
+        let lines: Vec<Value> = String::from_utf8(lines).unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].get("port"), None);
+        assert_eq!(lines[0]["suite"], "Group");
+        assert_eq!(lines[0]["name"], "First");
+        assert_eq!(lines[0]["status"], "passed");
+        assert_eq!(lines[1]["port"], "node");
+        assert_eq!(lines[1]["status"], "failed");
+    }
+}
@@ -0,0 +1,164 @@
+/*
+ * File: src/report/junit.rs
+ * Date: 15.10.2026
+ * Author: agent
+ * 
+ * MIT License
+ * 
+ * Copyright (c) 2026 MarkAtk
+ * 
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+ * of the Software, and to permit persons to whom the Software is furnished to do
+ * so, subject to the following conditions:
+ * 
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ * 
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+use std::io;
+use std::time::Duration;
+use crate::tests::{SuiteResult, TestResult, TestStatus};
+use crate::tests::result::seconds;
+
+/// Write test suite results as JUnit XML report.
+///
+/// Unnamed test suites are reported with the given name.
+pub fn write<W: io::Write>(writer: &mut W, name: &str, results: &[SuiteResult]) -> Result<(), io::Error> {
+    let mut content = String::new();
+
+    let mut total_tests = 0;
+    let mut total_failures = 0;
+    let mut total_errors = 0;
+    let mut total_skipped = 0;
+    let mut total_time = Duration::from_secs(0);
+
+    for result in results {
+        let suite_name = if result.name.is_empty() {
+            name
+        } else {
+            result.name.as_str()
+        };
+
+        // failed hooks are reported as additional test cases
+        let tests: Vec<&TestResult> = result.tests.iter().chain(result.failed_hooks()).collect();
+        let count = |status| tests.iter().filter(|test| test.status == status).count();
+
+        let failures = count(TestStatus::Failed);
+        let errors = count(TestStatus::Errored);
+        let skipped = count(TestStatus::Skipped) + count(TestStatus::AllowedFailure);
+        let time = result.duration();
+
+        content.push_str(&format!("  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" skipped=\"{}\" time=\"{:.3}\">\n", escape(suite_name), tests.len(), failures, errors, skipped, seconds(time)));
+
+        for test in &tests {
+            content.push_str(&test_case(test, suite_name));
+        }
+
+        content.push_str("  </testsuite>\n");
+
+        total_tests += tests.len();
+        total_failures += failures;
+        total_errors += errors;
+        total_skipped += skipped;
+        total_time += time;
+    }
+
+    writeln!(writer, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")?;
+    writeln!(writer, "<testsuites name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" skipped=\"{}\" time=\"{:.3}\">", escape(name), total_tests, total_failures, total_errors, total_skipped, seconds(total_time))?;
+    write!(writer, "{}", content)?;
+    writeln!(writer, "</testsuites>")?;
+
+    Ok(())
+}
+
+fn test_case(test: &TestResult, suite_name: &str) -> String {
+    let mut test_name = if test.name.is_empty() {
+        test.input.clone()
+    } else {
+        test.name.clone()
+    };
+
+    if let Some(hook) = test.hook {
+        test_name = format!("{}: {}", hook, test_name);
+    }
+
+    let mut content = format!("    <testcase name=\"{}\" classname=\"{}\" time=\"{:.3}\"", escape(&test_name), escape(suite_name), seconds(test.duration));
+    let message = escape(&test.failure_message().unwrap_or_default());
+
+    match test.status {
+        TestStatus::Passed => content.push_str("/>\n"),
+        TestStatus::Failed => {
+            let details = format!("Expected: {}\nReceived: {}", test.expected, test.response_text().unwrap_or_default());
+
+            content.push_str(&format!(">\n      <failure message=\"{}\">{}</failure>\n    </testcase>\n", message, escape(&details).replace("&#10;", "\n")));
+        },
+        TestStatus::Errored => content.push_str(&format!(">\n      <error message=\"{}\"/>\n    </testcase>\n", message)),
+        TestStatus::AllowedFailure => content.push_str(&format!(">\n      <skipped message=\"Allowed failure: {}\"/>\n    </testcase>\n", message)),
+        TestStatus::Skipped => content.push_str(&format!(">\n      <skipped message=\"{}\"/>\n    </testcase>\n", message))
+    };
+
+    content
+}
+
+fn escape(text: &str) -> String {
+    let mut result = String::new();
+
+    for ch in text.chars() {
+        match ch {
+            '&' => result.push_str("&amp;"),
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '"' => result.push_str("&quot;"),
+            '\'' => result.push_str("&apos;"),
+            '\n' => result.push_str("&#10;"),
+            '\r' => result.push_str("&#13;"),
+            '\t' => result.push_str("&#9;"),
+            // other control characters are not allowed in XML 1.0
+            ch if ch.is_control() => result.push_str(&format!("\\x{:02X}", ch as u32)),
+            ch => result.push(ch)
+        };
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::serial::transport::MockTransport;
+    use crate::tests::{TestCase, TestSuite};
+
+    #[test]
+    fn write_report() {
+        let mut ports = MockTransport::ports(&[("a", "ok"), ("b", "<no>")]);
+
+        let mut allowed = TestCase::new(String::new(), "c".to_string(), "ok".to_string());
+        allowed.settings.allow_failure = Some(true);
+
+        let mut test_suite = TestSuite::new("Group".to_string());
+        test_suite.push(TestCase::new("First".to_string(), "a".to_string(), "ok".to_string()));
+        test_suite.push(TestCase::new("Second".to_string(), "b".to_string(), "ok".to_string()));
+        test_suite.push(allowed);
+
+        let result = test_suite.run(&mut ports);
+
+        let mut report = Vec::new();
+        super::write(&mut report, "script", &[result]).unwrap();
+
+        let report = String::from_utf8(report).unwrap();
+
+        assert!(report.contains("<testsuite name=\"Group\" tests=\"3\" failures=\"1\" errors=\"0\" skipped=\"1\""));
+        assert!(report.contains("<failure message=\"expected &apos;ok&apos; but received &apos;&lt;no&gt;&apos;\">"));
+        assert!(report.contains("<skipped message=\"Allowed failure: Connection timed out\"/>"));
+    }
+}
@@ -28,83 +28,308 @@
 
 use std::io;
 use std::fs::File;
+use std::path::Path;
+use std::thread;
 use clap::{ArgMatches, SubCommand, Arg, App};
 use colored::*;
-use serial_unit_testing::serial::Serial;
-use serial_unit_testing::parser;
-use serial_unit_testing::tests::TestCaseSettings;
+use serial_unit_testing::serial::{Serial, PortRegistry};
+use serial_unit_testing::tests::{Script, SuiteResult, TestSuite, TestCaseSettings};
+use serial_unit_testing::report::{junit, json};
 use crate::commands;
+use crate::error::Error;
 
-pub fn run(matches: &ArgMatches) -> Result<(), String> {
+/// Results of running all test suites on one port.
+struct PortRun {
+    port: String,
+    results: Vec<SuiteResult>,
+    successful: usize,
+    failed: usize
+}
+
+pub fn run(matches: &ArgMatches) -> Result<(), Error> {
     let filename = matches.value_of("file").unwrap();
 
-    let mut file = match File::open(filename) {
-        Ok(file) => file,
-        Err(ref e) if e.kind() == io::ErrorKind::NotFound => return Err("File not found".to_string()), 
-        Err(e) => return Err(format!("{}", e))
+    // parse tests
+    let mut default_test_settings = TestCaseSettings::default();
+    default_test_settings.verbose = Some(matches.is_present("verbose"));
+
+    let config = commands::load_config(matches)?;
+
+    if let Some(ref config) = config {
+        default_test_settings.merge_weak(&config.test_settings);
+    }
+
+    let mut script = commands::parse_script(matches, default_test_settings)?;
+
+    // open all serial ports before running any test
+    let (mut ports, mut named_ports) = commands::open_serial_ports(matches, config.as_ref(), &script)?;
+
+    // declared ports not given as argument are opened from the port profile with the same name
+    for declaration in &script.ports {
+        if named_ports.iter().any(|(port, _)| *port == declaration.name) {
+            continue;
+        }
+
+        if let Some(serial) = commands::open_profile(matches, &declaration.name, config.as_ref(), Some(&declaration.settings))? {
+            named_ports.push((declaration.name.clone(), serial));
+        }
+    }
+
+    check_ports(&script, &ports, &named_ports)?;
+
+    let stop_on_failure = matches.is_present("stop");
+    let format = matches.value_of("format").unwrap();
+    let text_output = format == "text";
+
+    let runs = if ports.len() > 1 {
+        run_parallel(ports, &script.test_suites, format, stop_on_failure)?
+    } else {
+        let mut registry = PortRegistry::new();
+        let mut port = String::new();
+
+        if let Some((name, serial)) = ports.pop() {
+            port = name;
+            registry.set_default(serial);
+        }
+
+        for (name, serial) in named_ports {
+            registry.insert(name, serial);
+        }
+
+        vec!(run_port(port, &mut script.test_suites, &mut registry, format, stop_on_failure, false))
     };
 
-    // open serial
-    let (settings, port_name) = commands::get_serial_settings(matches).unwrap();
+    let successful_tests: usize = runs.iter().map(|run| run.successful).sum();
+    let failed_tests: usize = runs.iter().map(|run| run.failed).sum();
+    let total_tests = successful_tests + failed_tests;
 
-    let mut serial = Serial::open_with_settings(port_name, &settings)?;
+    if text_output {
+        if runs.len() > 1 {
+            println!();
 
-    // parse and run tests
-    let mut default_test_settings = TestCaseSettings::default();
-    default_test_settings.verbose = Some(matches.is_present("verbose"));
+            for run in &runs {
+                println!("{}: {} tests, {} successful, {} failed", run.port, (run.successful + run.failed).to_string().yellow(), run.successful.to_string().green(), run.failed.to_string().red());
+            }
+        }
+
+        println!("\nRan {} tests, {} successful, {} failed", total_tests.to_string().yellow(), successful_tests.to_string().green(), failed_tests.to_string().red());
+    } else if format == "json" {
+        let written = if runs.len() == 1 {
+            json::write(&mut io::stdout(), &runs[0].results)
+        } else {
+            let results: Vec<(String, Vec<SuiteResult>)> = runs.iter().map(|run| (run.port.clone(), run.results.clone())).collect();
+
+            json::write_ports(&mut io::stdout(), &results)
+        };
+
+        if let Err(e) = written {
+            return Err(Error::Other(format!("Unable to write results: {}", e)));
+        }
+    }
+
+    if let Some(junit_filename) = matches.value_of("junit") {
+        write_junit_report(junit_filename, filename, &runs)?;
+    }
+
+    check_results(&runs)
+}
+
+/// Get the error of a run with failed tests, runs with tests failed because no response was received in time
+/// exit with the timeout code.
+fn check_results(runs: &[PortRun]) -> Result<(), Error> {
+    let failed_tests: usize = runs.iter().map(|run| run.failed).sum();
+
+    if failed_tests == 0 {
+        return Ok(());
+    }
+
+    let timed_out_tests: usize = runs.iter()
+        .flat_map(|run| run.results.iter())
+        .map(|result| result.timed_out())
+        .sum();
+
+    if timed_out_tests > 0 {
+        return Err(Error::Timeout(format!("{} of {} failed tests timed out", timed_out_tests, failed_tests)));
+    }
+
+    Err(Error::TestFailure)
+}
+
+/// Check all ports declared by the script are assigned and all ports used by tests are given.
+fn check_ports(script: &Script, ports: &[(String, Serial)], named_ports: &[(String, Serial)]) -> Result<(), Error> {
+    for (name, _) in named_ports {
+        if script.port(name).is_none() {
+            return Err(Error::Other(format!("Port '{}' is not declared in the script", name)));
+        }
+    }
+
+    for name in script.ports.iter().map(|port| &port.name) {
+        if named_ports.iter().all(|(port, _)| port != name) {
+            return Err(Error::Other(format!("Port '{}' is not assigned, add {}=<port> to the ports or a port profile to the configuration", name, name)));
+        }
+    }
+
+    if script.ports.is_empty() == false && ports.len() > 1 {
+        return Err(Error::Other("Scripts with declared ports can only run on one default port".to_string()));
+    }
+
+    if ports.is_empty() && script.uses_default_port() {
+        return Err(Error::Other("Tests without port option need a default port".to_string()));
+    }
+
+    Ok(())
+}
+
+/// Run the test suites on all ports at the same time, each port in its own thread.
+fn run_parallel(ports: Vec<(String, Serial)>, test_suites: &[TestSuite], format: &str, stop_on_failure: bool) -> Result<Vec<PortRun>, Error> {
+    let handles: Vec<(String, thread::JoinHandle<PortRun>)> = ports.into_iter().map(|(port, serial)| {
+        let mut test_suites = test_suites.to_vec();
+        let mut registry = PortRegistry::from(serial);
+        let format = format.to_string();
+        let name = port.clone();
+
+        (name, thread::spawn(move || run_port(port, &mut test_suites, &mut registry, &format, stop_on_failure, true)))
+    }).collect();
+
+    let mut runs = Vec::new();
+
+    for (port, handle) in handles {
+        match handle.join() {
+            Ok(run) => runs.push(run),
+            Err(_) => return Err(Error::Other(format!("Running tests on {} failed unexpectedly", port)))
+        };
+    }
+
+    Ok(runs)
+}
+
+/// Run all test suites on the port. With multiple ports running at once each output line is prefixed with the port.
+fn run_port(port: String, test_suites: &mut [TestSuite], ports: &mut PortRegistry, format: &str, stop_on_failure: bool, parallel: bool) -> PortRun {
+    let text_output = format == "text";
+    let prefix = format!("[{}] ", port);
 
-    let test_suites = match parser::parse_file_with_default_settings(&mut file, default_test_settings) {
-        Ok(test_suites) => test_suites,
-        Err(e) => return Err(format!("Unable to parse file: {}", e))
+    let mut run = PortRun {
+        port,
+        results: Vec::new(),
+        successful: 0,
+        failed: 0
     };
 
-    let mut total_tests = 0;
-    let mut successful_tests = 0;
-    let mut failed_tests = 0;
+    let mut stopped = false;
 
-    let stop_on_failure = matches.is_present("stop");
+    for test_suite in test_suites.iter_mut() {
+        // remaining groups are reported as skipped after a global stop
+        if stopped {
+            run.results.push(test_suite.skip());
+
+            continue;
+        }
 
-    for mut test_suite in test_suites {
         // only set stop_on_failure and never reset
         if stop_on_failure {
             test_suite.settings.stop_on_failure = stop_on_failure;
         }
 
-        let result = test_suite.run_and_print(&mut serial);
+        let result = match format {
+            "jsonl" => {
+                let name = test_suite.name.clone();
+                let port = if parallel { Some(run.port.as_str()) } else { None };
+                let stdout = io::stdout();
+
+                test_suite.run_with_callback(ports, |_, result| {
+                    // lock per line so lines of other ports are not mixed in,
+                    // stdout being closed must not abort the test run
+                    let _ = json::write_port_line(&mut stdout.lock(), port, &name, result);
+                })
+            },
+            "json" => test_suite.run(ports),
+            _ if parallel => {
+                let group = if test_suite.name.is_empty() { String::new() } else { format!("{}: ", test_suite.name) };
 
-        let successful = test_suite.successful();
-        let failed = test_suite.failed();
+                test_suite.run_with_callback(ports, |test, result| {
+                    // hooks are only shown if they fail
+                    if result.hook.is_some() && result.is_successful() {
+                        return;
+                    }
 
-        total_tests += successful + failed;
-        successful_tests += successful;
-        failed_tests += failed;
+                    let hook = result.hook.map(|hook| format!("{}: ", hook)).unwrap_or_default();
 
-        println!();
+                    println!("{}{}{}{}", prefix.cyan(), group, hook, test.format_result(result));
+                })
+            },
+            _ => test_suite.run_and_print(ports)
+        };
 
-        if result == false && test_suite.settings.stop_on_failure {
+        run.successful += result.successful();
+        run.failed += result.failed();
+
+        if text_output && parallel == false {
+            println!();
+        }
+
+        if result.stopped {
             // global stop on failure?
-            if stop_on_failure {
-                println!("Stopping because 'stop-on-failure' is set");
+            stopped = stop_on_failure;
 
-                break;
-            } else {
-                println!("Stopping group because 'stop-on-failure' is set\n");
+            let prefix = if parallel { prefix.as_str() } else { "" };
 
-                continue;
+            if text_output && stop_on_failure {
+                println!("{}Stopping because 'stop-on-failure' is set", prefix);
+            } else if text_output {
+                println!("{}Stopping group because 'stop-on-failure' is set\n", prefix);
             }
         }
+
+        run.results.push(result);
     }
 
-    println!("\nRan {} tests, {} successful, {} failed", total_tests.to_string().yellow(), successful_tests.to_string().green(), failed_tests.to_string().red());
+    run
+}
 
-    Ok(())
+fn write_junit_report(filename: &str, script_filename: &str, runs: &[PortRun]) -> Result<(), String> {
+    let mut file = match File::create(filename) {
+        Ok(file) => file,
+        Err(e) => return Err(format!("Unable to create report file: {}", e))
+    };
+
+    let name = match Path::new(script_filename).file_stem() {
+        _ if script_filename == "-" => "stdin".to_string(),
+        Some(stem) => stem.to_string_lossy().to_string(),
+        None => script_filename.to_string()
+    };
+
+    let results = port_results(&name, runs);
+
+    match junit::write(&mut file, &name, &results) {
+        Ok(_) => Ok(()),
+        Err(e) => Err(format!("Unable to write report file: {}", e))
+    }
+}
+
+/// Get the results of all runs, suites of multiple ports are named after their port.
+fn port_results(name: &str, runs: &[PortRun]) -> Vec<SuiteResult> {
+    if runs.len() == 1 {
+        return runs[0].results.clone();
+    }
+
+    runs.iter().flat_map(|run| run.results.iter().map(move |result| {
+        let mut result = result.clone();
+
+        result.name = if result.name.is_empty() {
+            format!("{}: {}", run.port, name)
+        } else {
+            format!("{}: {}", run.port, result.name)
+        };
+
+        result
+    })).collect()
 }
 
 pub fn command<'a>() -> App<'a, 'a> {
     SubCommand::with_name("run")
         .about("Run script on serial port")
         .arg(Arg::with_name("file")
-            .help("Script to run on the serial port")
+            .help("Script to run on the serial port, - to read the script from stdin")
             .required(true)
             .takes_value(true))
         .args(commands::serial_arguments(true, false).as_slice())
@@ -116,4 +341,107 @@ pub fn command<'a>() -> App<'a, 'a> {
             .long("verbose")
             .short("v")
             .help("Show verbose output"))
+        .arg(Arg::with_name("junit")
+            .long("junit")
+            .help("Write JUnit XML report to file")
+            .takes_value(true))
+        .arg(Arg::with_name("format")
+            .long("format")
+            .help("Output format, json prints all results at the end, jsonl prints one line per test")
+            .takes_value(true)
+            .possible_values(&["text", "json", "jsonl"])
+            .default_value("text"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use serial_unit_testing::serial::{Serial, PortRegistry};
+    use serial_unit_testing::parser::Parser;
+    use serial_unit_testing::tests::{TestCase, TestSuite};
+    use crate::error;
+
+    /// Run the test suites on an echoing mock port without printing results.
+    fn run_on_mock(port: &str, test_suites: &mut [TestSuite]) -> super::PortRun {
+        let mut ports = PortRegistry::from(Serial::open("mock://").unwrap());
+
+        super::run_port(port.to_string(), test_suites, &mut ports, "json", false, false)
+    }
+
+    fn suite(tests: Vec<TestCase>) -> TestSuite {
+        let mut test_suite = TestSuite::new("Group".to_string());
+
+        for mut test in tests {
+            test.settings.timeout = Some(Duration::from_millis(10));
+            test_suite.push(test);
+        }
+
+        test_suite
+    }
+
+    #[test]
+    fn map_results_to_exit_codes() {
+        let passed = run_on_mock("a", &mut [suite(vec!(TestCase::new(String::new(), "ok".to_string(), "ok".to_string())))]);
+        let failed = run_on_mock("b", &mut [suite(vec!(TestCase::new(String::new(), "ok".to_string(), "no".to_string())))]);
+        let timed_out = run_on_mock("c", &mut [suite(vec!(TestCase::new_expect(String::new(), "ready".to_string())))]);
+
+        assert!(super::check_results(&[passed]).is_ok());
+
+        let err = super::check_results(&[failed]).unwrap_err();
+
+        assert_eq!(err.exit_code(), error::EXIT_TEST_FAILURE);
+
+        // timeouts on any port exit with the timeout code
+        let failed = run_on_mock("b", &mut [suite(vec!(TestCase::new(String::new(), "ok".to_string(), "no".to_string())))]);
+        let err = super::check_results(&[failed, timed_out]).unwrap_err();
+
+        assert_eq!(err.exit_code(), error::EXIT_TIMEOUT);
+        assert_eq!(err.to_string(), "1 of 2 failed tests timed out");
+    }
+
+    #[test]
+    fn run_on_several_ports() {
+        let ports = vec!(("a".to_string(), Serial::open("mock://").unwrap()), ("b".to_string(), Serial::open("mock://").unwrap()));
+        let test_suites = [suite(vec!(
+            TestCase::new(String::new(), "ok".to_string(), "ok".to_string()),
+            TestCase::new(String::new(), "ok".to_string(), "no".to_string())
+        ))];
+
+        let runs = super::run_parallel(ports, &test_suites, "json", false).unwrap();
+        let counts: Vec<(&str, usize, usize)> = runs.iter().map(|run| (run.port.as_str(), run.successful, run.failed)).collect();
+
+        assert_eq!(counts, vec!(("a", 1, 1), ("b", 1, 1)));
+        assert_eq!(runs[1].results[0].tests.len(), 2);
+    }
+
+    #[test]
+    fn name_suites_after_their_port() {
+        let mut test_suites = [suite(vec!(TestCase::new(String::new(), "ok".to_string(), "ok".to_string()))), TestSuite::new(String::new())];
+        let runs = [run_on_mock("a", &mut test_suites), run_on_mock("b", &mut test_suites)];
+
+        let names: Vec<String> = super::port_results("script", &runs).into_iter().map(|result| result.name).collect();
+
+        assert_eq!(names, vec!("a: Group", "a: script", "b: Group", "b: script"));
+        assert_eq!(super::port_results("script", &runs[..1])[0].name, "Group");
+    }
+
+    #[test]
+    fn check_assigned_ports() {
+        let script = Parser::new().parse_script_str("[@port node]\n(Join, port=node) : \"joined\"\n\"ping\" : \"pong\"\n").unwrap();
+        let port = |name: &str| (name.to_string(), Serial::open("mock://").unwrap());
+
+        assert!(super::check_ports(&script, &[port("a")], &[port("node")]).is_ok());
+
+        let err = super::check_ports(&script, &[port("a"), port("b")], &[port("node")]).unwrap_err();
+
+        assert_eq!(err.to_string(), "Scripts with declared ports can only run on one default port");
+
+        let err = super::check_ports(&script, &[port("a")], &[]).unwrap_err();
+
+        assert_eq!(err.to_string(), "Port 'node' is not assigned, add node=<port> to the ports or a port profile to the configuration");
+
+        let err = super::check_ports(&script, &[], &[port("node")]).unwrap_err();
+
+        assert_eq!(err.to_string(), "Tests without port option need a default port");
+    }
 }
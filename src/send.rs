@@ -29,19 +29,19 @@
 use std::io::{self, Write};
 use clap::{ArgMatches, SubCommand, Arg, App};
 use serial_unit_testing::utils;
-use serial_unit_testing::serial::Serial;
+use serial_unit_testing::serial::{Serial, Framing};
 use crate::commands;
+use crate::error::Error;
 
-pub fn run(matches: &ArgMatches) -> Result<(), String> {
-    let (settings, port_name) = commands::get_serial_settings(matches).unwrap();
-
-    let mut serial = Serial::open_with_settings(port_name, &settings)?;
+pub fn run(matches: &ArgMatches) -> Result<(), Error> {
+    let mut serial = commands::open_serial(matches)?;
 
     let mut text = matches.value_of("text").unwrap().to_string();
     let echo_text = matches.is_present("echo");
 
     let input_text_format = commands::get_text_input_format(matches);
     let output_text_format = commands::get_text_output_format(matches);
+    let framing = commands::get_framing(matches)?;
 
     if matches.is_present("newline") {
         match input_text_format {
@@ -69,8 +69,8 @@ pub fn run(matches: &ArgMatches) -> Result<(), String> {
 
     match serial.write_format(&text, &input_text_format) {
         Ok(_) => (),
-        Err(ref e) if e.kind() == io::ErrorKind::TimedOut => return Err("Serial connection timed out".to_string()),
-        Err(e) => return Err(format!("Error sending text {:?}", e))
+        Err(ref e) if e.kind() == io::ErrorKind::TimedOut => return Err(Error::Timeout("Serial connection timed out".to_string())),
+        Err(e) => return Err(Error::Other(format!("Error sending text {:?}", e)))
     };
 
     if echo_text {
@@ -78,7 +78,7 @@ pub fn run(matches: &ArgMatches) -> Result<(), String> {
     }
 
     if matches.is_present("response") {
-        read_response(&mut serial, &output_text_format).unwrap();
+        read_response(&mut serial, &output_text_format, &framing)?;
     }
 
     Ok(())
@@ -90,6 +90,7 @@ pub fn command<'a>() -> App<'a, 'a> {
         .args(commands::serial_arguments(false, true).as_slice())
         .args(commands::text_input_arguments().as_slice())
         .args(commands::text_output_arguments().as_slice())
+        .args(commands::framing_arguments().as_slice())
         .arg(Arg::with_name("echo")
             .long("echo")
             .short("e")
@@ -104,9 +105,30 @@ pub fn command<'a>() -> App<'a, 'a> {
             .takes_value(true))
 }
 
-fn read_response(serial: &mut Serial, text_format: &utils::TextFormat) -> Result<(), String> {
+fn read_response(serial: &mut Serial, text_format: &utils::TextFormat, framing: &Option<Framing>) -> Result<(), String> {
     let mut row_entries = 0;
 
+    // framed responses are read once, otherwise everything until the timeout is shown
+    if let Some(framing) = framing {
+        // stdout may be closed early, e.g. when piped into head
+        let written = match serial.read_frame(framing) {
+            Ok(bytes) => {
+                match text_format {
+                    utils::TextFormat::Text => io::stdout().write_all(&bytes).and_then(|_| writeln!(io::stdout())),
+                    _ => {
+                        utils::print_radix_string(&bytes, text_format, &mut row_entries);
+
+                        writeln!(io::stdout())
+                    }
+                }
+            },
+            Err(ref e) if e.kind() == io::ErrorKind::TimedOut => writeln!(io::stdout()),
+            Err(e) => return Err(format!("{:?}", e))
+        };
+
+        return written.map_err(|e| format!("Unable to write response: {}", e));
+    }
+
     loop {
         match serial.read() {
             Ok(bytes) => {
@@ -28,22 +28,62 @@
 
 use std::boxed::Box;
 use std::io;
-use std::str;
-use std::time::Duration;
-use serialport;
+use std::time::{Duration, Instant};
 use crate::utils;
 
 pub mod settings;
+pub mod transport;
+pub mod registry;
+pub mod usb;
+
+use self::transport::Transport;
+pub use self::registry::PortRegistry;
+
+/// Maximum number of bytes of a framed response.
+pub const MAX_FRAME_LENGTH: usize = 64 * 1024;
 
 pub struct Serial {
-    port: Box<serialport::SerialPort>,
-    read_buffer: Vec<u8>
+    port: Box<dyn Transport>,
+    settings: settings::Settings,
+    read_buffer: Vec<u8>,
+    pending: Vec<u8>
+}
+
+/// How the end of a response is detected.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Framing {
+    /// The response ends with the terminator sequence.
+    Terminator(Vec<u8>),
+    /// The response has a fixed number of bytes.
+    Length(usize),
+    /// The response ends once no data was received for the duration.
+    Idle(Duration)
+}
+
+impl Framing {
+    /// Get the length of the response if it is complete, data after it belongs to the next response.
+    pub fn frame_length(&self, response: &[u8]) -> Option<usize> {
+        match *self {
+            Framing::Terminator(ref terminator) => {
+                if terminator.is_empty() {
+                    return None;
+                }
+
+                response.windows(terminator.len())
+                    .position(|window| window == terminator.as_slice())
+                    .map(|position| position + terminator.len())
+            },
+            Framing::Length(length) if response.len() >= length => Some(length),
+            _ => None
+        }
+    }
 }
 
 pub struct CheckSettings {
     pub ignore_case: bool,
     pub input_format: utils::TextFormat,
-    pub output_format: utils::TextFormat
+    pub output_format: utils::TextFormat,
+    pub framing: Option<Framing>
 }
 
 impl Default for CheckSettings {
@@ -51,7 +91,8 @@ impl Default for CheckSettings {
         CheckSettings {
             ignore_case: false,
             input_format: utils::TextFormat::Text,
-            output_format: utils::TextFormat::Text
+            output_format: utils::TextFormat::Text,
+            framing: None
         }
     }
 }
@@ -64,43 +105,129 @@ impl Serial {
     }
 
     pub fn open_with_settings(port_name: &str, settings: &settings::Settings) -> Result<Serial, String> {
-        match serialport::open_with_settings(&port_name, &settings.to_serial_port_settings()) {
-            Ok(port) => {
-                Ok(Serial { port, read_buffer: vec![0; 1000] })
-            },
-            Err(e) => Err(format!("Error opening port {:?}", e))
-        }
+        let port = transport::open(port_name, settings)?;
+
+        let mut serial = Serial::with_transport(port);
+        serial.settings = settings.clone();
+
+        Ok(serial)
+    }
+
+    pub fn with_transport(port: Box<dyn Transport>) -> Serial {
+        let settings = settings::Settings {
+            timeout: port.timeout().as_millis() as u64,
+            ..Default::default()
+        };
+
+        Serial { port, settings, read_buffer: vec![0; 1000], pending: Vec::new() }
+    }
+
+    pub fn name(&self) -> Option<String> {
+        self.port.name()
+    }
+
+    /// Get the timeout of reads on the port.
+    pub fn timeout(&self) -> Duration {
+        self.port.timeout()
+    }
+
+    pub fn settings(&self) -> &settings::Settings {
+        &self.settings
+    }
+
+    /// Change the serial settings of the open port, e.g. after the device changed its baud rate.
+    pub fn set_settings(&mut self, settings: settings::Settings) -> Result<(), io::Error> {
+        self.port.set_settings(&settings)?;
+        self.settings = settings;
+
+        Ok(())
     }
 
     pub fn write(&mut self, text: &str) -> Result<(), io::Error> {
-        self.port.write(text.as_bytes())?;
+        self.port.write_all(text.as_bytes())?;
 
         Ok(())
     }
 
     pub fn write_format(&mut self, text: &str, text_format: &utils::TextFormat) -> Result<(), io::Error> {
-        let bytes = match text_format {
-            utils::TextFormat::Binary => utils::bytes_from_binary_string(text).unwrap(),
-            utils::TextFormat::Hex => utils::bytes_from_hex_string(text).unwrap(),
-            _ => {
-                let mut bytes = Vec::new();
-                bytes.extend_from_slice(text.as_bytes());
-
-                bytes
-            }
+        let bytes = match utils::bytes_from_format(text, text_format) {
+            Ok(bytes) => bytes,
+            Err(e) => return Err(io::Error::new(io::ErrorKind::InvalidInput, e))
         };
 
-        self.port.write(bytes.as_slice())?;
+        self.port.write_all(bytes.as_slice())?;
 
         Ok(())
     }
 
     pub fn read(&mut self) -> Result<&[u8], io::Error> {
-        let length = self.port.read(&mut self.read_buffer)?;
+        let length = self.read_port()?;
 
         Ok(&self.read_buffer[..length])
     }
 
+    /// Keep data to be returned by the next read, e.g. received after the end of a response.
+    pub fn unread(&mut self, data: &[u8]) {
+        let mut pending = data.to_vec();
+        pending.append(&mut self.pending);
+
+        self.pending = pending;
+    }
+
+    /// Read one response framed as given, data received after the end of the response is kept for the next read.
+    pub fn read_frame(&mut self, framing: &Framing) -> Result<Vec<u8>, io::Error> {
+        let timeout = self.port.timeout();
+
+        self.read_frame_with_timeout(framing, timeout)
+    }
+
+    /// Read one response framed as given. The whole frame must be received within the timeout and may not be longer
+    /// than `MAX_FRAME_LENGTH`, devices may keep sending data without ever ending a frame.
+    pub fn read_frame_with_timeout(&mut self, framing: &Framing, timeout: Duration) -> Result<Vec<u8>, io::Error> {
+        let mut response = Vec::new();
+        let start = Instant::now();
+
+        loop {
+            let remaining = match timeout.checked_sub(start.elapsed()) {
+                Some(remaining) if remaining > Duration::from_millis(0) => remaining,
+                _ => return Err(io::Error::new(io::ErrorKind::TimedOut, format!("Frame not complete within {}ms", timeout.as_millis())))
+            };
+
+            // after the first data only wait for the idle gap
+            let read_timeout = match *framing {
+                Framing::Idle(gap) if response.is_empty() == false => gap.min(remaining),
+                _ => remaining
+            };
+
+            match self.read_with_timeout(read_timeout) {
+                Ok(bytes) => {
+                    response.extend_from_slice(bytes);
+
+                    if let Some(length) = framing.frame_length(&response) {
+                        let rest = response.split_off(length);
+                        self.unread(&rest);
+
+                        break;
+                    }
+
+                    if response.len() > MAX_FRAME_LENGTH {
+                        return Err(io::Error::new(io::ErrorKind::InvalidData, format!("Frame longer than {} bytes", MAX_FRAME_LENGTH)));
+                    }
+                },
+                Err(ref e) if e.kind() == io::ErrorKind::TimedOut => {
+                    if response.is_empty() {
+                        return Err(io::Error::new(io::ErrorKind::TimedOut, "Connection timed out"));
+                    }
+
+                    break;
+                },
+                Err(e) => return Err(e)
+            }
+        }
+
+        Ok(response)
+    }
+
     pub fn read_str(&mut self) -> Result<String, io::Error> {
         self.read_str_with_format(utils::TextFormat::Text)
     }
@@ -118,14 +245,15 @@ impl Serial {
     }
 
     pub fn read_min_str_with_format(&mut self, min_length: usize, format: utils::TextFormat) -> Result<String, io::Error> {
+        let mut response_bytes = Vec::new();
         let mut response = String::new();
 
         loop {
             match self.read() {
                 Ok(bytes) => {
-                    let new_text = utils::radix_string(bytes, &format);
-
-                    response.push_str(new_text.as_str());
+                    // convert all bytes at once, characters may be split between chunks
+                    response_bytes.extend_from_slice(bytes);
+                    response = utils::radix_string(&response_bytes, &format);
 
                     if response.len() >= min_length {
                         break;
@@ -150,9 +278,11 @@ impl Serial {
         let old_timeout = self.port.timeout();
         self.port.set_timeout(timeout)?;
 
-        let length = self.port.read(&mut self.read_buffer)?;
+        let length = self.read_port();
 
+        // restore timeout even if reading timed out
         self.port.set_timeout(old_timeout)?;
+        let length = length?;
 
         Ok(&self.read_buffer[..length])
     }
@@ -162,11 +292,13 @@ impl Serial {
         let old_timeout = self.port.timeout();
         self.port.set_timeout(timeout)?;
 
-        let length = self.port.read(&mut self.read_buffer)?;
+        let length = self.read_port();
 
+        // restore timeout even if reading timed out
         self.port.set_timeout(old_timeout)?;
+        let length = length?;
 
-        Ok(str::from_utf8(&self.read_buffer[..length]).unwrap().to_string())
+        Ok(String::from_utf8_lossy(&self.read_buffer[..length]).to_string())
     }
 
     pub fn read_str_with_format_and_timeout(&mut self, format: utils::TextFormat, timeout: Duration) -> Result<String, io::Error> {
@@ -174,10 +306,11 @@ impl Serial {
         let old_timeout = self.port.timeout();
         self.port.set_timeout(timeout)?;
 
-        let length = self.port.read(&mut self.read_buffer)?;
-        let data = &self.read_buffer[..length];
+        let length = self.read_port();
 
+        // restore timeout even if reading timed out
         self.port.set_timeout(old_timeout)?;
+        let data = &self.read_buffer[..length?];
 
         let result = utils::radix_string(data, &format);
 
@@ -203,19 +336,31 @@ impl Serial {
     }
 
     pub fn check_read_with_settings(&mut self, desired_response: &str, settings: &CheckSettings) -> Result<(bool, String), io::Error> {
+        if let Some(ref framing) = settings.framing {
+            let response_bytes = self.read_frame(framing)?;
+            let mut response = utils::radix_string(&response_bytes, &settings.output_format);
+
+            if settings.ignore_case {
+                response = response.to_lowercase();
+            }
+
+            return Ok((desired_response == response, response));
+        }
+
+        let mut response_bytes = Vec::new();
         let mut response = String::new();
 
         loop {
             match self.read() {
                 Ok(bytes) => {
-                    let mut new_text = utils::radix_string(bytes, &settings.output_format);
+                    // convert all bytes at once, characters may be split between chunks
+                    response_bytes.extend_from_slice(bytes);
+                    response = utils::radix_string(&response_bytes, &settings.output_format);
 
                     if settings.ignore_case {
-                        new_text = new_text.to_lowercase();
+                        response = response.to_lowercase();
                     }
 
-                    response.push_str(new_text.as_str());
-
                     if desired_response == response {
                         break;
                     }
@@ -237,4 +382,59 @@ impl Serial {
 
         Ok((desired_response == response, response))
     }
+
+    /// Read into the read buffer, data kept from previous reads is returned first.
+    fn read_port(&mut self) -> Result<usize, io::Error> {
+        if self.pending.is_empty() {
+            return self.port.read(&mut self.read_buffer);
+        }
+
+        let length = self.pending.len().min(self.read_buffer.len());
+        self.read_buffer[..length].copy_from_slice(&self.pending[..length]);
+        self.pending.drain(..length);
+
+        Ok(length)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+    use std::time::Duration;
+    use super::{Serial, Framing};
+    use super::transport::MockTransport;
+
+    #[test]
+    fn read_framed_responses() {
+        let mut transport = MockTransport::new();
+        transport.push_unsolicited(b"OK\r\n> 0123456789");
+
+        let mut serial = Serial::with_transport(Box::new(transport));
+
+        assert_eq!(serial.read_frame(&Framing::Terminator(b"\r\n".to_vec())).unwrap(), b"OK\r\n");
+        assert_eq!(serial.read_frame(&Framing::Terminator(b">".to_vec())).unwrap(), b">");
+        assert_eq!(serial.read_frame(&Framing::Length(5)).unwrap(), b" 0123");
+        assert_eq!(serial.read_frame(&Framing::Idle(Duration::from_millis(10))).unwrap(), b"456789");
+        assert!(serial.read_frame(&Framing::Length(1)).is_err());
+    }
+
+    #[test]
+    fn stop_reading_frames_never_ending() {
+        let mut transport = MockTransport::new();
+        transport.set_stream(b"log ");
+
+        let mut serial = Serial::with_transport(Box::new(transport));
+        let err = serial.read_frame_with_timeout(&Framing::Terminator(b"\r\n".to_vec()), Duration::from_millis(20)).unwrap_err();
+
+        assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+        assert_eq!(err.to_string(), "Frame not complete within 20ms");
+
+        let mut transport = MockTransport::new();
+        transport.set_stream(&[b'.'; 1000]);
+
+        let mut serial = Serial::with_transport(Box::new(transport));
+        let err = serial.read_frame_with_timeout(&Framing::Terminator(b"\r\n".to_vec()), Duration::from_secs(60)).unwrap_err();
+
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
 }
@@ -0,0 +1,82 @@
+/*
+ * File: src/serial/registry.rs
+ * Date: 16.10.2026
+ * Author: agent
+ * 
+ * MIT License
+ * 
+ * Copyright (c) 2026 MarkAtk
+ * 
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+ * of the Software, and to permit persons to whom the Software is furnished to do
+ * so, subject to the following conditions:
+ * 
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ * 
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+use std::collections::HashMap;
+use crate::serial::Serial;
+
+/// Serial ports a script runs on, the default port and ports declared with a name.
+pub struct PortRegistry {
+    default: Option<Serial>,
+    named: HashMap<String, Serial>
+}
+
+impl PortRegistry {
+    pub fn new() -> PortRegistry {
+        PortRegistry {
+            default: None,
+            named: HashMap::new()
+        }
+    }
+
+    /// Set the port used by tests without `port` option.
+    pub fn set_default(&mut self, serial: Serial) {
+        self.default = Some(serial);
+    }
+
+    /// Add a port used by tests with the `port` option set to the name.
+    pub fn insert(&mut self, name: String, serial: Serial) {
+        self.named.insert(name, serial);
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.named.contains_key(name)
+    }
+
+    /// Get the port with the given name, or the default port if no name is given.
+    pub fn get(&mut self, name: Option<&str>) -> Result<&mut Serial, String> {
+        match name {
+            Some(name) => self.named.get_mut(name).ok_or_else(|| format!("Port '{}' is not open", name)),
+            None => self.default.as_mut().ok_or_else(|| "No default port is open".to_string())
+        }
+    }
+}
+
+impl Default for PortRegistry {
+    fn default() -> PortRegistry {
+        PortRegistry::new()
+    }
+}
+
+impl From<Serial> for PortRegistry {
+    fn from(serial: Serial) -> PortRegistry {
+        let mut registry = PortRegistry::new();
+        registry.set_default(serial);
+
+        registry
+    }
+}
@@ -26,11 +26,12 @@
  * SOFTWARE.
  */
 
+use std::str::FromStr;
 use std::time::Duration;
 
 use serialport;
 
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Clone, Copy)]
 pub enum DataBits {
     Five,
     Six,
@@ -38,26 +39,79 @@ pub enum DataBits {
     Eight
 }
 
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Clone, Copy)]
 pub enum Parity {
     None,
     Even,
     Odd
 }
 
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Clone, Copy)]
 pub enum StopBits {
     One,
     Two
 }
 
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Clone, Copy)]
 pub enum FlowControl {
     None,
     Software,
     Hardware
 }
 
+impl FromStr for DataBits {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<DataBits, String> {
+        match value {
+            "5" => Ok(DataBits::Five),
+            "6" => Ok(DataBits::Six),
+            "7" => Ok(DataBits::Seven),
+            "8" => Ok(DataBits::Eight),
+            _ => Err(format!("Invalid data bits '{}'", value))
+        }
+    }
+}
+
+impl FromStr for Parity {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Parity, String> {
+        match value {
+            "none" => Ok(Parity::None),
+            "even" => Ok(Parity::Even),
+            "odd" => Ok(Parity::Odd),
+            _ => Err(format!("Invalid parity '{}'", value))
+        }
+    }
+}
+
+impl FromStr for StopBits {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<StopBits, String> {
+        match value {
+            "1" => Ok(StopBits::One),
+            "2" => Ok(StopBits::Two),
+            _ => Err(format!("Invalid stop bits '{}'", value))
+        }
+    }
+}
+
+impl FromStr for FlowControl {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<FlowControl, String> {
+        match value {
+            "none" => Ok(FlowControl::None),
+            "software" => Ok(FlowControl::Software),
+            "hardware" => Ok(FlowControl::Hardware),
+            _ => Err(format!("Invalid flow control '{}'", value))
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct Settings {
     pub baud_rate: u32,
     pub timeout: u64,
@@ -105,6 +159,62 @@ impl Settings {
     }
 }
 
+/// Serial settings changing only the settings which are set, e.g. settings of a script or a port profile.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PartialSettings {
+    pub baud_rate: Option<u32>,
+    /// Timeout in milliseconds.
+    pub timeout: Option<u64>,
+
+    pub data_bits: Option<DataBits>,
+    pub parity: Option<Parity>,
+    pub stop_bits: Option<StopBits>,
+    pub flow_control: Option<FlowControl>
+}
+
+impl PartialSettings {
+    /// Overwrite the settings with all settings which are set.
+    pub fn apply(&self, settings: &mut Settings) {
+        if let Some(baud_rate) = self.baud_rate {
+            settings.baud_rate = baud_rate;
+        }
+
+        if let Some(timeout) = self.timeout {
+            settings.timeout = timeout;
+        }
+
+        if let Some(data_bits) = self.data_bits {
+            settings.data_bits = data_bits;
+        }
+
+        if let Some(parity) = self.parity {
+            settings.parity = parity;
+        }
+
+        if let Some(stop_bits) = self.stop_bits {
+            settings.stop_bits = stop_bits;
+        }
+
+        if let Some(flow_control) = self.flow_control {
+            settings.flow_control = flow_control;
+        }
+    }
+
+    /// Overwrite the settings set in other.
+    pub fn merge(&mut self, other: &PartialSettings) {
+        self.baud_rate = other.baud_rate.or(self.baud_rate);
+        self.timeout = other.timeout.or(self.timeout);
+        self.data_bits = other.data_bits.or(self.data_bits);
+        self.parity = other.parity.or(self.parity);
+        self.stop_bits = other.stop_bits.or(self.stop_bits);
+        self.flow_control = other.flow_control.or(self.flow_control);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == PartialSettings::default()
+    }
+}
+
 impl Default for Settings {
     fn default() -> Settings {
         Settings {
@@ -0,0 +1,189 @@
+/*
+ * File: src/serial/transport/mock.rs
+ * Date: 15.10.2026
+ * Author: agent
+ * 
+ * MIT License
+ * 
+ * Copyright (c) 2026 MarkAtk
+ * 
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+ * of the Software, and to permit persons to whom the Software is furnished to do
+ * so, subject to the following conditions:
+ * 
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ * 
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+use std::collections::VecDeque;
+use std::io;
+use std::time::Duration;
+use super::Transport;
+#[cfg(test)]
+use crate::serial::{Serial, PortRegistry};
+
+/// In-memory transport replying with scripted responses.
+///
+/// Each written request is compared against the next scripted request and, once it was fully
+/// received, the matching response is queued for reading. Data not matching the script is dropped.
+pub struct MockTransport {
+    script: VecDeque<(Vec<u8>, Vec<u8>)>,
+    received: Vec<u8>,
+    pending: VecDeque<u8>,
+    echo: bool,
+    chunk_size: Option<usize>,
+    stream: Vec<u8>,
+    timeout: Duration
+}
+
+impl MockTransport {
+    pub fn new() -> MockTransport {
+        MockTransport {
+            script: VecDeque::new(),
+            received: Vec::new(),
+            pending: VecDeque::new(),
+            echo: false,
+            chunk_size: None,
+            stream: Vec::new(),
+            timeout: Duration::from_millis(1000)
+        }
+    }
+
+    /// Create a mock sending back everything written to it.
+    pub fn echo() -> MockTransport {
+        let mut transport = MockTransport::new();
+        transport.echo = true;
+
+        transport
+    }
+
+    pub fn push(&mut self, request: &[u8], response: &[u8]) {
+        self.script.push_back((request.to_vec(), response.to_vec()));
+    }
+
+    /// Queue data to be read without any request.
+    pub fn push_unsolicited(&mut self, response: &[u8]) {
+        self.pending.extend(response);
+    }
+
+    /// Return at most the number of bytes per read, like data arriving in pieces.
+    pub fn set_chunk_size(&mut self, chunk_size: usize) {
+        self.chunk_size = Some(chunk_size);
+    }
+
+    /// Send the data on every read once all other data was read, like a device logging continuously.
+    pub fn set_stream(&mut self, data: &[u8]) {
+        self.stream = data.to_vec();
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.script.is_empty() && self.pending.is_empty()
+    }
+}
+
+#[cfg(test)]
+impl MockTransport {
+    /// Create a registry with a mock default port answering each request with its response.
+    pub fn ports(exchanges: &[(&str, &str)]) -> PortRegistry {
+        let mut transport = MockTransport::new();
+
+        for &(request, response) in exchanges {
+            transport.push(request.as_bytes(), response.as_bytes());
+        }
+
+        transport.into_ports()
+    }
+
+    /// Create a registry with the mock as default port.
+    pub fn into_ports(self) -> PortRegistry {
+        PortRegistry::from(Serial::with_transport(Box::new(self)))
+    }
+}
+
+impl Default for MockTransport {
+    fn default() -> MockTransport {
+        MockTransport::new()
+    }
+}
+
+impl io::Read for MockTransport {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, io::Error> {
+        if self.pending.is_empty() {
+            self.pending.extend(&self.stream);
+        }
+
+        if self.pending.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::TimedOut, "Connection timed out"));
+        }
+
+        let mut length = buf.len().min(self.pending.len());
+
+        if let Some(chunk_size) = self.chunk_size {
+            length = length.min(chunk_size);
+        }
+
+        for (target, byte) in buf.iter_mut().zip(self.pending.drain(..length)) {
+            *target = byte;
+        }
+
+        Ok(length)
+    }
+}
+
+impl io::Write for MockTransport {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, io::Error> {
+        if self.echo {
+            self.pending.extend(buf);
+
+            return Ok(buf.len());
+        }
+
+        self.received.extend_from_slice(buf);
+
+        while let Some((request, _)) = self.script.front() {
+            if self.received.starts_with(request) {
+                let (request, response) = self.script.pop_front().unwrap();
+
+                self.received.drain(..request.len());
+                self.pending.extend(response);
+
+                continue;
+            }
+
+            if request.starts_with(&self.received) == false {
+                self.received.clear();
+            }
+
+            break;
+        }
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> Result<(), io::Error> {
+        Ok(())
+    }
+}
+
+impl Transport for MockTransport {
+    fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    fn set_timeout(&mut self, timeout: Duration) -> Result<(), io::Error> {
+        self.timeout = timeout;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,111 @@
+/*
+ * File: src/serial/transport/mod.rs
+ * Date: 15.10.2026
+ * Author: agent
+ * 
+ * MIT License
+ * 
+ * Copyright (c) 2026 MarkAtk
+ * 
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+ * of the Software, and to permit persons to whom the Software is furnished to do
+ * so, subject to the following conditions:
+ * 
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ * 
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+use std::io;
+use std::time::Duration;
+use super::settings::Settings;
+use super::usb;
+
+mod serial_port;
+mod tcp;
+#[cfg(unix)]
+mod pty;
+mod mock;
+
+pub use self::serial_port::SerialPortTransport;
+pub use self::tcp::TcpTransport;
+#[cfg(unix)]
+pub use self::pty::PtyTransport;
+pub use self::mock::MockTransport;
+
+pub const TCP_PREFIX: &str = "tcp://";
+pub const PTY_PREFIX: &str = "pty://";
+pub const MOCK_PREFIX: &str = "mock://";
+
+/// Byte stream a `Serial` reads from and writes to.
+///
+/// Reads must fail with `io::ErrorKind::TimedOut` if no data arrived within the timeout.
+pub trait Transport: io::Read + io::Write + Send {
+    fn timeout(&self) -> Duration;
+
+    fn set_timeout(&mut self, timeout: Duration) -> Result<(), io::Error>;
+
+    /// Change the serial settings of the open port, transports without serial settings only change the timeout.
+    fn set_settings(&mut self, settings: &Settings) -> Result<(), io::Error> {
+        self.set_timeout(Duration::from_millis(settings.timeout))
+    }
+
+    fn name(&self) -> Option<String> {
+        None
+    }
+}
+
+/// Open a transport for the given port name.
+///
+/// Port names starting with `tcp://`, `pty://` or `mock://` select the matching transport, USB selectors like
+/// `usb:0403:6001` open the matching serial port and every other name is opened as serial port device.
+pub fn open(port_name: &str, settings: &Settings) -> Result<Box<dyn Transport>, String> {
+    if let Some(address) = port_name.strip_prefix(TCP_PREFIX) {
+        let transport = TcpTransport::connect(address, Duration::from_millis(settings.timeout))?;
+
+        return Ok(Box::new(transport));
+    }
+
+    if port_name.starts_with(PTY_PREFIX) {
+        return open_pty(settings);
+    }
+
+    if port_name.starts_with(MOCK_PREFIX) {
+        let mut transport = MockTransport::echo();
+        transport.set_timeout(Duration::from_millis(settings.timeout)).unwrap();
+
+        return Ok(Box::new(transport));
+    }
+
+    // ports selected by their USB properties, e.g. usb:0403:6001
+    let port_name = usb::resolve(port_name)?;
+    let transport = SerialPortTransport::open(&port_name, settings)?;
+
+    Ok(Box::new(transport))
+}
+
+#[cfg(unix)]
+fn open_pty(settings: &Settings) -> Result<Box<dyn Transport>, String> {
+    let mut transport = PtyTransport::open()?;
+
+    if let Err(e) = transport.set_timeout(Duration::from_millis(settings.timeout)) {
+        return Err(format!("Error opening pseudo terminal {}", e));
+    }
+
+    Ok(Box::new(transport))
+}
+
+#[cfg(not(unix))]
+fn open_pty(_settings: &Settings) -> Result<Box<dyn Transport>, String> {
+    Err("Pseudo terminals are not supported on this platform".to_string())
+}
@@ -0,0 +1,88 @@
+/*
+ * File: src/serial/transport/pty.rs
+ * Date: 15.10.2026
+ * Author: agent
+ * 
+ * MIT License
+ * 
+ * Copyright (c) 2026 MarkAtk
+ * 
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+ * of the Software, and to permit persons to whom the Software is furnished to do
+ * so, subject to the following conditions:
+ * 
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ * 
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+use std::io;
+use std::time::Duration;
+use serialport::SerialPort;
+use serialport::posix::TTYPort;
+use super::Transport;
+
+/// Pseudo terminal pair. Data is read from and written to the master side while other
+/// programs connect to the slave device returned by `name`.
+pub struct PtyTransport {
+    master: TTYPort,
+    slave: TTYPort
+}
+
+impl PtyTransport {
+    pub fn open() -> Result<PtyTransport, String> {
+        let (master, mut slave) = match TTYPort::pair() {
+            Ok(pair) => pair,
+            Err(e) => return Err(format!("Error opening pseudo terminal {:?}", e))
+        };
+
+        // the slave stays open to keep the master readable, but other programs must be able to open it too
+        if let Err(e) = slave.set_exclusive(false) {
+            return Err(format!("Error opening pseudo terminal {:?}", e));
+        }
+
+        Ok(PtyTransport { master, slave })
+    }
+}
+
+impl io::Read for PtyTransport {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, io::Error> {
+        self.master.read(buf)
+    }
+}
+
+impl io::Write for PtyTransport {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, io::Error> {
+        self.master.write(buf)
+    }
+
+    fn flush(&mut self) -> Result<(), io::Error> {
+        self.master.flush()
+    }
+}
+
+impl Transport for PtyTransport {
+    fn timeout(&self) -> Duration {
+        self.master.timeout()
+    }
+
+    fn set_timeout(&mut self, timeout: Duration) -> Result<(), io::Error> {
+        self.master.set_timeout(timeout)?;
+
+        Ok(())
+    }
+
+    fn name(&self) -> Option<String> {
+        self.slave.name()
+    }
+}
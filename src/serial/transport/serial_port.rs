@@ -0,0 +1,84 @@
+/*
+ * File: src/serial/transport/serial_port.rs
+ * Date: 15.10.2026
+ * Author: agent
+ * 
+ * MIT License
+ * 
+ * Copyright (c) 2026 MarkAtk
+ * 
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+ * of the Software, and to permit persons to whom the Software is furnished to do
+ * so, subject to the following conditions:
+ * 
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ * 
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+use std::io;
+use std::time::Duration;
+use serialport;
+use super::Transport;
+use crate::serial::settings::Settings;
+
+pub struct SerialPortTransport {
+    port: Box<dyn serialport::SerialPort>
+}
+
+impl SerialPortTransport {
+    pub fn open(port_name: &str, settings: &Settings) -> Result<SerialPortTransport, String> {
+        match serialport::open_with_settings(&port_name, &settings.to_serial_port_settings()) {
+            Ok(port) => Ok(SerialPortTransport { port }),
+            Err(e) => Err(format!("Error opening port {:?}", e))
+        }
+    }
+}
+
+impl io::Read for SerialPortTransport {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, io::Error> {
+        self.port.read(buf)
+    }
+}
+
+impl io::Write for SerialPortTransport {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, io::Error> {
+        self.port.write(buf)
+    }
+
+    fn flush(&mut self) -> Result<(), io::Error> {
+        self.port.flush()
+    }
+}
+
+impl Transport for SerialPortTransport {
+    fn timeout(&self) -> Duration {
+        self.port.timeout()
+    }
+
+    fn set_timeout(&mut self, timeout: Duration) -> Result<(), io::Error> {
+        self.port.set_timeout(timeout)?;
+
+        Ok(())
+    }
+
+    fn set_settings(&mut self, settings: &Settings) -> Result<(), io::Error> {
+        self.port.set_all(&settings.to_serial_port_settings())?;
+
+        Ok(())
+    }
+
+    fn name(&self) -> Option<String> {
+        self.port.name()
+    }
+}
@@ -0,0 +1,116 @@
+/*
+ * File: src/serial/transport/tcp.rs
+ * Date: 15.10.2026
+ * Author: agent
+ * 
+ * MIT License
+ * 
+ * Copyright (c) 2026 MarkAtk
+ * 
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+ * of the Software, and to permit persons to whom the Software is furnished to do
+ * so, subject to the following conditions:
+ * 
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ * 
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+use std::io;
+use std::net::TcpStream;
+use std::time::Duration;
+use super::Transport;
+
+/// Raw TCP connection, e.g. to a ser2net server.
+pub struct TcpTransport {
+    stream: TcpStream,
+    address: String,
+    timeout: Duration
+}
+
+impl TcpTransport {
+    pub fn connect(address: &str, timeout: Duration) -> Result<TcpTransport, String> {
+        let stream = match TcpStream::connect(address) {
+            Ok(stream) => stream,
+            Err(e) => return Err(format!("Error connecting to {}: {}", address, e))
+        };
+
+        TcpTransport::from_stream(stream, address.to_string(), timeout)
+    }
+
+    pub fn from_stream(stream: TcpStream, address: String, timeout: Duration) -> Result<TcpTransport, String> {
+        let mut transport = TcpTransport {
+            stream,
+            address,
+            timeout
+        };
+
+        if let Err(e) = transport.set_timeout(timeout) {
+            return Err(format!("Error connecting to {}: {}", transport.address, e));
+        }
+
+        if let Err(e) = transport.stream.set_nodelay(true) {
+            return Err(format!("Error connecting to {}: {}", transport.address, e));
+        }
+
+        Ok(transport)
+    }
+}
+
+impl io::Read for TcpTransport {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, io::Error> {
+        match self.stream.read(buf) {
+            // a closed connection would otherwise be read as an endless stream of empty chunks
+            Ok(0) if buf.is_empty() == false => Err(io::Error::new(io::ErrorKind::ConnectionAborted, "Connection closed")),
+            Ok(length) => Ok(length),
+            // unix reports socket timeouts as would block
+            Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => Err(io::Error::new(io::ErrorKind::TimedOut, "Connection timed out")),
+            Err(e) => Err(e)
+        }
+    }
+}
+
+impl io::Write for TcpTransport {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, io::Error> {
+        self.stream.write(buf)
+    }
+
+    fn flush(&mut self) -> Result<(), io::Error> {
+        self.stream.flush()
+    }
+}
+
+impl Transport for TcpTransport {
+    fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    fn set_timeout(&mut self, timeout: Duration) -> Result<(), io::Error> {
+        // zero durations are rejected by the socket, use the smallest possible instead
+        let socket_timeout = if timeout == Duration::from_secs(0) {
+            Duration::from_millis(1)
+        } else {
+            timeout
+        };
+
+        self.stream.set_read_timeout(Some(socket_timeout))?;
+        self.stream.set_write_timeout(Some(socket_timeout))?;
+        self.timeout = timeout;
+
+        Ok(())
+    }
+
+    fn name(&self) -> Option<String> {
+        Some(self.address.clone())
+    }
+}
@@ -0,0 +1,189 @@
+/*
+ * File: src/serial/usb.rs
+ * Date: 16.10.2026
+ * Author: agent
+ * 
+ * MIT License
+ * 
+ * Copyright (c) 2026 MarkAtk
+ * 
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+ * of the Software, and to permit persons to whom the Software is furnished to do
+ * so, subject to the following conditions:
+ * 
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ * 
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+use std::fmt;
+use std::str::FromStr;
+use serialport::{self, SerialPortType, UsbPortInfo};
+
+pub const USB_PREFIX: &str = "usb:";
+pub const USB_SERIAL_PREFIX: &str = "usb-serial:";
+pub const PRODUCT_PREFIX: &str = "product:";
+
+/// USB serial port selected by its properties instead of its OS specific name, which may change on every reboot.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Selector {
+    /// Vendor and product id, written as `usb:0403:6001`.
+    Id(u16, u16),
+    /// Serial number of the device, written as `usb-serial:A12345`.
+    SerialNumber(String),
+    /// Part of the product string, ignoring case, written as `product:"CP2102"`.
+    Product(String)
+}
+
+impl Selector {
+    /// Check if the port name is a selector, e.g. `usb:0403:6001`.
+    pub fn is_selector(port_name: &str) -> bool {
+        port_name.starts_with(USB_PREFIX) || port_name.starts_with(USB_SERIAL_PREFIX) || port_name.starts_with(PRODUCT_PREFIX)
+    }
+
+    pub fn matches(&self, info: &UsbPortInfo) -> bool {
+        match *self {
+            Selector::Id(vid, pid) => info.vid == vid && info.pid == pid,
+            Selector::SerialNumber(ref serial_number) => info.serial_number.as_ref() == Some(serial_number),
+            Selector::Product(ref product) => info.product.as_ref().is_some_and(|name| name.to_lowercase().contains(&product.to_lowercase()))
+        }
+    }
+
+    /// Find the name of the only available port matching the selector.
+    pub fn find_port(&self) -> Result<String, String> {
+        let ports = match serialport::available_ports() {
+            Ok(ports) => ports,
+            Err(e) => return Err(format!("Unable to list ports: {}", e))
+        };
+
+        let matches: Vec<String> = ports.into_iter()
+            .filter(|port| match port.port_type {
+                SerialPortType::UsbPort(ref info) => self.matches(info),
+                _ => false
+            })
+            .map(|port| port.port_name)
+            .collect();
+
+        match matches.len() {
+            0 => Err("No matching USB port found".to_string()),
+            1 => Ok(matches[0].clone()),
+            _ => Err(format!("Multiple matching USB ports found: {}", matches.join(", ")))
+        }
+    }
+}
+
+impl FromStr for Selector {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Selector, String> {
+        if let Some(serial_number) = value.strip_prefix(USB_SERIAL_PREFIX) {
+            let serial_number = unquote(serial_number);
+
+            if serial_number.is_empty() {
+                return Err("Missing serial number, expected usb-serial:<serial number>".to_string());
+            }
+
+            return Ok(Selector::SerialNumber(serial_number.to_string()));
+        }
+
+        if let Some(product) = value.strip_prefix(PRODUCT_PREFIX) {
+            let product = unquote(product);
+
+            if product.is_empty() {
+                return Err("Missing product, expected product:\"<product>\"".to_string());
+            }
+
+            return Ok(Selector::Product(product.to_string()));
+        }
+
+        if let Some(id) = value.strip_prefix(USB_PREFIX) {
+            return match parse_id(id) {
+                Some((vid, pid)) => Ok(Selector::Id(vid, pid)),
+                None => Err("Invalid USB id, expected usb:<vid>:<pid>".to_string())
+            };
+        }
+
+        Err(format!("Invalid port selector '{}'", value))
+    }
+}
+
+impl fmt::Display for Selector {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Selector::Id(vid, pid) => formatter.write_fmt(format_args!("{}{:04x}:{:04x}", USB_PREFIX, vid, pid)),
+            Selector::SerialNumber(ref serial_number) => formatter.write_fmt(format_args!("{}{}", USB_SERIAL_PREFIX, serial_number)),
+            Selector::Product(ref product) => formatter.write_fmt(format_args!("{}\"{}\"", PRODUCT_PREFIX, product))
+        }
+    }
+}
+
+/// Get the name of the port to open, selectors are resolved to the name of the matching port.
+pub fn resolve(port_name: &str) -> Result<String, String> {
+    if Selector::is_selector(port_name) == false {
+        return Ok(port_name.to_string());
+    }
+
+    port_name.parse::<Selector>()?.find_port()
+}
+
+/// Parse a USB id written as `VID:PID` in hexadecimal, e.g. `0403:6001`.
+pub fn parse_id(id: &str) -> Option<(u16, u16)> {
+    let mut parts = id.split(':');
+
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(vid), Some(pid), None) => {
+            match (u16::from_str_radix(vid, 16), u16::from_str_radix(pid, 16)) {
+                (Ok(vid), Ok(pid)) => Some((vid, pid)),
+                _ => None
+            }
+        },
+        _ => None
+    }
+}
+
+fn unquote(value: &str) -> &str {
+    if value.len() >= 2 && value.starts_with('"') && value.ends_with('"') {
+        return &value[1..value.len() - 1];
+    }
+
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use serialport::UsbPortInfo;
+    use super::Selector;
+
+    #[test]
+    fn parse_and_match_selectors() {
+        let info = UsbPortInfo {
+            vid: 0x0403,
+            pid: 0x6001,
+            serial_number: Some("A12345".to_string()),
+            manufacturer: Some("FTDI".to_string()),
+            product: Some("FT232R USB UART".to_string())
+        };
+
+        assert_eq!("usb:0403:6001".parse(), Ok(Selector::Id(0x0403, 0x6001)));
+        assert_eq!("usb-serial:A12345".parse(), Ok(Selector::SerialNumber("A12345".to_string())));
+        assert_eq!("product:\"CP2102\"".parse(), Ok(Selector::Product("CP2102".to_string())));
+        assert!("usb:0403".parse::<Selector>().is_err());
+        assert!("product:".parse::<Selector>().is_err());
+
+        assert!(Selector::Id(0x0403, 0x6001).matches(&info));
+        assert!(Selector::SerialNumber("A12345".to_string()).matches(&info));
+        assert!(Selector::Product("ft232r".to_string()).matches(&info));
+        assert!(!Selector::Product("CP2102".to_string()).matches(&info));
+        assert_eq!(Selector::Id(0x10c4, 0xea60).to_string(), "usb:10c4:ea60");
+    }
+}
@@ -0,0 +1,151 @@
+/*
+ * File: src/simulate.rs
+ * Date: 15.10.2026
+ * Author: agent
+ * 
+ * MIT License
+ * 
+ * Copyright (c) 2026 MarkAtk
+ * 
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+ * of the Software, and to permit persons to whom the Software is furnished to do
+ * so, subject to the following conditions:
+ * 
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ * 
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+use std::io;
+use std::net::TcpListener;
+use std::time::Duration;
+use clap::{ArgMatches, SubCommand, Arg, App};
+use serial_unit_testing::utils;
+use serial_unit_testing::simulator::Simulator;
+use serial_unit_testing::serial::transport::{Transport, TcpTransport};
+#[cfg(unix)]
+use serial_unit_testing::serial::transport::PtyTransport;
+use crate::commands;
+use crate::error::Error;
+
+pub fn run(matches: &ArgMatches) -> Result<(), Error> {
+    let test_suites = commands::parse_script(matches, Default::default())?.test_suites;
+
+    let mut simulator = Simulator::from_test_suites(&test_suites)?;
+
+    for message in simulator.skipped() {
+        eprintln!("{}", message);
+    }
+    let verbose = matches.is_present("verbose");
+
+    if let Some(address) = matches.value_of("listen") {
+        return listen(address, &mut simulator, verbose);
+    }
+
+    open_pty(&mut simulator, verbose)
+}
+
+pub fn command<'a>() -> App<'a, 'a> {
+    SubCommand::with_name("simulate")
+        .about("Simulate the device side of a script on a pseudo terminal or TCP socket")
+        .arg(Arg::with_name("file")
+            .help("Script to simulate")
+            .required(true)
+            .takes_value(true))
+        .arg(Arg::with_name("listen")
+            .long("listen")
+            .short("l")
+            .help("Listen for TCP connections on the given address instead of opening a pseudo terminal")
+            .takes_value(true))
+        .arg(Arg::with_name("verbose")
+            .long("verbose")
+            .short("v")
+            .help("Show verbose output"))
+}
+
+fn listen(address: &str, simulator: &mut Simulator, verbose: bool) -> Result<(), Error> {
+    let listener = match TcpListener::bind(address) {
+        Ok(listener) => listener,
+        Err(e) => return Err(Error::PortError(format!("Unable to listen on {}: {}", address, e)))
+    };
+
+    println!("Listening on {}", address);
+
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => return Err(Error::PortError(format!("Unable to accept connection: {}", e)))
+        };
+
+        let peer = match stream.peer_addr() {
+            Ok(peer) => peer.to_string(),
+            Err(_) => String::new()
+        };
+
+        let mut transport = TcpTransport::from_stream(stream, peer.clone(), Duration::from_millis(100))?;
+
+        println!("Connection from {}", peer);
+
+        simulator.reset();
+
+        if let Err(e) = simulate(&mut transport, simulator, verbose) {
+            println!("Connection closed: {}", e);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(unix)]
+fn open_pty(simulator: &mut Simulator, verbose: bool) -> Result<(), Error> {
+    let mut transport = match PtyTransport::open() {
+        Ok(transport) => transport,
+        Err(e) => return Err(Error::PortError(e))
+    };
+
+    if let Err(e) = transport.set_timeout(Duration::from_millis(100)) {
+        return Err(Error::PortError(format!("Error opening pseudo terminal {}", e)));
+    }
+
+    println!("Simulating on {}", transport.name().unwrap_or_default());
+
+    match simulate(&mut transport, simulator, verbose) {
+        Ok(_) => Ok(()),
+        Err(e) => Err(Error::Other(format!("{}", e)))
+    }
+}
+
+#[cfg(not(unix))]
+fn open_pty(_simulator: &mut Simulator, _verbose: bool) -> Result<(), Error> {
+    Err(Error::PortError("Pseudo terminals are not supported on this platform, use --listen instead".to_string()))
+}
+
+fn simulate(transport: &mut dyn Transport, simulator: &mut Simulator, verbose: bool) -> Result<(), io::Error> {
+    let mut buffer = vec![0; 1000];
+
+    loop {
+        let length = match transport.read(&mut buffer) {
+            Ok(length) => length,
+            Err(ref e) if e.kind() == io::ErrorKind::TimedOut => continue,
+            Err(e) => return Err(e)
+        };
+
+        if let Some(output) = simulator.receive(&buffer[..length]) {
+            transport.write_all(&output)?;
+
+            if verbose {
+                println!("Replied '{}'", utils::escape_bytes(&output));
+            }
+        }
+    }
+}
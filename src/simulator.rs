@@ -0,0 +1,227 @@
+/*
+ * File: src/simulator.rs
+ * Date: 15.10.2026
+ * Author: agent
+ * 
+ * MIT License
+ * 
+ * Copyright (c) 2026 MarkAtk
+ * 
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+ * of the Software, and to permit persons to whom the Software is furnished to do
+ * so, subject to the following conditions:
+ * 
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ * 
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+use crate::tests::{TestSuite, TestStep};
+use crate::tests::byte_pattern;
+
+struct Response {
+    input: Vec<u8>,
+    outputs: Vec<Vec<u8>>,
+    next_output: usize
+}
+
+/// Device side of a test script.
+///
+/// Whenever the received data ends with the input of a test the output of that test is replied.
+/// Tests sharing the same input are replied in script order, starting over after the last one.
+pub struct Simulator {
+    responses: Vec<Response>,
+    received: Vec<u8>,
+    max_input_length: usize,
+    skipped: Vec<String>
+}
+
+impl Simulator {
+    pub fn new() -> Simulator {
+        Simulator {
+            responses: Vec::new(),
+            received: Vec::new(),
+            max_input_length: 0,
+            skipped: Vec::new()
+        }
+    }
+
+    /// Create a simulator replying the tests of the suites, tests without a concrete output are skipped.
+    pub fn from_test_suites(test_suites: &[TestSuite]) -> Result<Simulator, String> {
+        let mut simulator = Simulator::new();
+
+        for test_suite in test_suites {
+            let hooks = test_suite.hooks().iter().map(|(_, test)| test);
+
+            for test in test_suite.tests().iter().chain(hooks) {
+                // each sent content is answered with the expected content following it
+                let mut input: Option<Vec<u8>> = None;
+                let mut responses = Vec::new();
+                let mut skip_reason = None;
+
+                for step in test.steps() {
+                    match step {
+                        TestStep::Send(_, _) => input = Some(step.bytes()?),
+                        TestStep::Expect(ref content, ref format) => {
+                            if let Some(input) = input.take() {
+                                // outputs with byte wildcards are replied with bytes matching them
+                                let output = match byte_pattern::example(content, format) {
+                                    Some(bytes) => Ok(bytes),
+                                    None => step.bytes()
+                                };
+
+                                match output {
+                                    Ok(output) => responses.push((input, output)),
+                                    Err(_) => {
+                                        skip_reason = Some(format!("Skipped test {}, unable to generate a response matching '{}'", test.to_string(), content));
+
+                                        break;
+                                    }
+                                }
+                            }
+                        }
+                    };
+                }
+
+                match skip_reason {
+                    Some(reason) => simulator.skipped.push(reason),
+                    None => {
+                        for (input, output) in responses {
+                            simulator.push(input, output);
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(simulator)
+    }
+
+    pub fn push(&mut self, input: Vec<u8>, output: Vec<u8>) {
+        if input.is_empty() {
+            return;
+        }
+
+        if input.len() > self.max_input_length {
+            self.max_input_length = input.len();
+        }
+
+        if let Some(response) = self.responses.iter_mut().find(|response| response.input == input) {
+            response.outputs.push(output);
+
+            return;
+        }
+
+        self.responses.push(Response {
+            input,
+            outputs: vec![output],
+            next_output: 0
+        });
+    }
+
+    pub fn len(&self) -> usize {
+        self.responses.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.responses.is_empty()
+    }
+
+    /// Get the messages of tests not simulated because their output could not be generated.
+    pub fn skipped(&self) -> &[String] {
+        &self.skipped
+    }
+
+    /// Feed received data into the simulator and get the reply once a complete input was received.
+    pub fn receive(&mut self, data: &[u8]) -> Option<Vec<u8>> {
+        self.received.extend_from_slice(data);
+
+        let received = &self.received;
+
+        // prefer the longest matching input
+        let response = self.responses.iter_mut()
+            .filter(|response| received.ends_with(&response.input))
+            .max_by_key(|response| response.input.len());
+
+        if let Some(response) = response {
+            let output = response.outputs[response.next_output].clone();
+            response.next_output = (response.next_output + 1) % response.outputs.len();
+
+            self.received.clear();
+
+            return Some(output);
+        }
+
+        // only keep as much data as the longest input could need
+        if self.received.len() > self.max_input_length {
+            let excess = self.received.len() - self.max_input_length;
+
+            self.received.drain(..excess);
+        }
+
+        None
+    }
+
+    pub fn reset(&mut self) {
+        self.received.clear();
+
+        for response in self.responses.iter_mut() {
+            response.next_output = 0;
+        }
+    }
+}
+
+impl Default for Simulator {
+    fn default() -> Simulator {
+        Simulator::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser;
+    use super::Simulator;
+
+    #[test]
+    fn reply_to_complete_input() {
+        let mut simulator = Simulator::new();
+        simulator.push(b"ping\n".to_vec(), b"pong".to_vec());
+
+        assert_eq!(simulator.receive(b"pi"), None);
+        assert_eq!(simulator.receive(b"ng\n"), Some(b"pong".to_vec()));
+        assert_eq!(simulator.receive(b"noise ping\n"), Some(b"pong".to_vec()));
+    }
+
+    #[test]
+    fn cycle_through_outputs() {
+        let mut simulator = Simulator::new();
+        simulator.push(b"count".to_vec(), b"1".to_vec());
+        simulator.push(b"count".to_vec(), b"2".to_vec());
+
+        assert_eq!(simulator.len(), 1);
+        assert_eq!(simulator.receive(b"count"), Some(b"1".to_vec()));
+        assert_eq!(simulator.receive(b"count"), Some(b"2".to_vec()));
+        assert_eq!(simulator.receive(b"count"), Some(b"1".to_vec()));
+    }
+
+    #[test]
+    fn reply_wildcard_outputs() {
+        let test_suites = parser::parse_str("(Status)h\"01\" : h\"AA ?? ..{2}\"\n(Any)h\"02\" : h\"AA.*\"\n(Ping)\"ping\" : \"pong\"\n").unwrap();
+        let mut simulator = Simulator::from_test_suites(&test_suites).unwrap();
+
+        assert_eq!(simulator.len(), 2);
+        assert_eq!(simulator.receive(b"\x01"), Some(b"\xAA\x00\x00\x00".to_vec()));
+        assert_eq!(simulator.receive(b"ping"), Some(b"pong".to_vec()));
+        assert_eq!(simulator.skipped(), &["Skipped test Any \"02\", unable to generate a response matching 'AA.*'".to_string()]);
+    }
+}
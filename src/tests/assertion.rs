@@ -0,0 +1,203 @@
+/*
+ * File: tests/assertion.rs
+ * Date: 16.10.2026
+ * Author: agent
+ * 
+ * MIT License
+ * 
+ * Copyright (c) 2026 MarkAtk
+ * 
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+ * of the Software, and to permit persons to whom the Software is furnished to do
+ * so, subject to the following conditions:
+ * 
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ * 
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+use std::fmt;
+use crate::tests::variables::{self, Variables};
+
+/// Numeric comparison of a captured value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Comparison {
+    /// Value equals within the tolerance, e.g. `3.3±0.1` or `3.3+-0.1`.
+    Equal(f64, f64),
+    /// Value is between both values including them, e.g. `3.2..3.4`.
+    Range(f64, f64),
+    GreaterThan(f64),
+    GreaterOrEqual(f64),
+    LessThan(f64),
+    LessOrEqual(f64)
+}
+
+impl Comparison {
+    pub fn matches(&self, value: f64) -> bool {
+        match *self {
+            // allow for rounding errors of the difference, e.g. 3.4 - 3.3 being larger than 0.1
+            Comparison::Equal(expected, tolerance) => (value - expected).abs() <= tolerance + f64::EPSILON * value.abs().max(expected.abs()),
+            Comparison::Range(min, max) => min <= value && value <= max,
+            Comparison::GreaterThan(limit) => value > limit,
+            Comparison::GreaterOrEqual(limit) => value >= limit,
+            Comparison::LessThan(limit) => value < limit,
+            Comparison::LessOrEqual(limit) => value <= limit
+        }
+    }
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Comparison::Equal(expected, 0.0) => write!(formatter, "{}", expected),
+            Comparison::Equal(expected, tolerance) => write!(formatter, "{}±{}", expected, tolerance),
+            Comparison::Range(min, max) => write!(formatter, "{}..{}", min, max),
+            Comparison::GreaterThan(limit) => write!(formatter, "> {}", limit),
+            Comparison::GreaterOrEqual(limit) => write!(formatter, ">= {}", limit),
+            Comparison::LessThan(limit) => write!(formatter, "< {}", limit),
+            Comparison::LessOrEqual(limit) => write!(formatter, "<= {}", limit)
+        }
+    }
+}
+
+/// Assertion on the numeric value of a variable, e.g. `voltage == 3.3±0.1` or `voltage in 3.2..3.4`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Assertion {
+    pub variable: String,
+    pub comparison: Comparison
+}
+
+impl Assertion {
+    pub fn parse(text: &str) -> Result<Assertion, String> {
+        let text = text.trim();
+
+        let name_length = text.find(|ch: char| ch.is_alphanumeric() == false && ch != '_').unwrap_or(text.len());
+        let variable = text[..name_length].to_string();
+
+        if variable.is_empty() {
+            return Err("Missing variable name".to_string());
+        }
+
+        let rest = text[name_length..].trim_start();
+
+        // longer operators first so they are not taken for their prefix
+        let operators = [">=", "<=", "==", ">", "<", "in "];
+
+        let operator = match operators.iter().find(|operator| rest.starts_with(*operator)) {
+            Some(operator) => *operator,
+            None => return Err(format!("Missing comparison after '{}'", variable))
+        };
+
+        let value = rest[operator.len()..].trim();
+
+        let comparison = match operator {
+            "==" => parse_equal(value)?,
+            "in " => parse_range(value)?,
+            ">" => Comparison::GreaterThan(parse_number(value)?),
+            ">=" => Comparison::GreaterOrEqual(parse_number(value)?),
+            "<" => Comparison::LessThan(parse_number(value)?),
+            _ => Comparison::LessOrEqual(parse_number(value)?)
+        };
+
+        Ok(Assertion { variable, comparison })
+    }
+
+    /// Check the value of the variable, returns the failure message if the comparison does not match.
+    pub fn check(&self, variables: &Variables) -> Result<Option<String>, String> {
+        let text = variables::lookup(variables, &self.variable)?;
+
+        let value = match text.trim().parse::<f64>() {
+            Ok(value) => value,
+            Err(_) => return Err(format!("Value '{}' of variable '{}' is not a number", text, self.variable))
+        };
+
+        if self.comparison.matches(value) {
+            Ok(None)
+        } else {
+            Ok(Some(format!("{}: expected {}, got {}", self.variable, self.comparison, text.trim())))
+        }
+    }
+}
+
+impl fmt::Display for Assertion {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self.comparison {
+            Comparison::Equal(_, _) => write!(formatter, "{} == {}", self.variable, self.comparison),
+            Comparison::Range(_, _) => write!(formatter, "{} in {}", self.variable, self.comparison),
+            _ => write!(formatter, "{} {}", self.variable, self.comparison)
+        }
+    }
+}
+
+fn parse_equal(value: &str) -> Result<Comparison, String> {
+    let parts: Vec<&str> = if value.contains('±') {
+        value.splitn(2, '±').collect()
+    } else {
+        value.splitn(2, "+-").collect()
+    };
+
+    let expected = parse_number(parts[0])?;
+    let tolerance = match parts.get(1) {
+        Some(tolerance) => parse_number(tolerance)?.abs(),
+        None => 0.0
+    };
+
+    Ok(Comparison::Equal(expected, tolerance))
+}
+
+fn parse_range(value: &str) -> Result<Comparison, String> {
+    let parts: Vec<&str> = value.splitn(2, "..").collect();
+
+    if parts.len() < 2 {
+        return Err(format!("Invalid range '{}'", value));
+    }
+
+    let min = parse_number(parts[0])?;
+    let max = parse_number(parts[1])?;
+
+    if min > max {
+        return Err(format!("Invalid range '{}'", value));
+    }
+
+    Ok(Comparison::Range(min, max))
+}
+
+fn parse_number(value: &str) -> Result<f64, String> {
+    match value.trim().parse::<f64>() {
+        Ok(number) if number.is_finite() => Ok(number),
+        _ => Err(format!("Invalid number '{}'", value.trim()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Assertion, Comparison};
+    use crate::tests::Variables;
+
+    #[test]
+    fn check_assertions() {
+        let mut variables = Variables::new();
+        variables.insert("voltage".to_string(), "3.52".to_string());
+
+        let assertion = Assertion::parse("voltage == 3.3±0.1").unwrap();
+        assert_eq!(assertion.comparison, Comparison::Equal(3.3, 0.1));
+        assert_eq!(assertion.check(&variables), Ok(Some("voltage: expected 3.3±0.1, got 3.52".to_string())));
+
+        assert_eq!(Assertion::parse("voltage == 3.5 +- 0.05").unwrap().check(&variables), Ok(None));
+        assert_eq!(Assertion::parse("voltage in 3.2..3.6").unwrap().check(&variables), Ok(None));
+        assert_eq!(Assertion::parse("voltage<=3.5").unwrap().check(&variables), Ok(Some("voltage: expected <= 3.5, got 3.52".to_string())));
+        assert_eq!(Assertion::parse("current > 0").unwrap().check(&variables), Err("Undefined variable 'current'".to_string()));
+        assert!(Assertion::parse("voltage in 3.4..3.2").is_err());
+        assert!(Assertion::parse("== 3").is_err());
+    }
+}
@@ -0,0 +1,213 @@
+/*
+ * File: tests/byte_pattern.rs
+ * Date: 16.10.2026
+ * Author: agent
+ * 
+ * MIT License
+ * 
+ * Copyright (c) 2026 MarkAtk
+ * 
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+ * of the Software, and to permit persons to whom the Software is furnished to do
+ * so, subject to the following conditions:
+ * 
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ * 
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+use crate::utils::TextFormat;
+
+/// Compile output content in a radix format into a regex matching the raw response bytes.
+///
+/// Each byte is written as in the radix string of the format, `?` matches any digit, e.g. `A?` in hex matches
+/// 0xA0 to 0xAF and `??` any byte. `..` matches any byte and `..{4}` four arbitrary bytes. Decimal bytes must be
+/// separated with whitespace. Outputs not written as bytes, e.g. using other regex syntax, are not compiled.
+pub fn compile(output: &str, format: &TextFormat) -> Option<String> {
+    let is_byte_char = |ch: char| ch.is_ascii_hexdigit() || ch == '?' || ch == '.' || ch == '{' || ch == '}' || ch == ',' || ch.is_whitespace();
+
+    if *format == TextFormat::Text || output.trim().is_empty() || output.chars().all(is_byte_char) == false {
+        return None;
+    }
+
+    let mut pattern = String::new();
+
+    for token in tokenize(output, format)? {
+        if let Some(repetition) = token.strip_prefix("..") {
+            pattern.push('.');
+            pattern.push_str(repetition);
+
+            continue;
+        }
+
+        let values: Vec<u8> = (0..=255u8).filter(|value| matches_digits(&token, *value, format)).collect();
+
+        pattern.push_str(&byte_class(&values)?);
+    }
+
+    // bytes are matched exactly, independent of letter case
+    Some(format!("(?s-ui:{})", pattern))
+}
+
+/// Get bytes matching output content in a radix format, e.g. to reply them when simulating a device.
+///
+/// Each wildcard is replaced by the smallest matching byte, repeated wildcards by their minimum count.
+pub fn example(output: &str, format: &TextFormat) -> Option<Vec<u8>> {
+    compile(output, format)?;
+
+    let mut bytes = Vec::new();
+
+    for token in tokenize(output, format)? {
+        if let Some(repetition) = token.strip_prefix("..") {
+            let count = repetition.trim_start_matches('{').split([',', '}']).next().unwrap_or("");
+
+            bytes.resize(bytes.len() + count.parse().unwrap_or(1), 0);
+
+            continue;
+        }
+
+        bytes.push((0..=255u8).find(|value| matches_digits(&token, *value, format))?);
+    }
+
+    Some(bytes)
+}
+
+/// Split the output into byte tokens and `..` wildcards with optional repetition.
+fn tokenize(output: &str, format: &TextFormat) -> Option<Vec<String>> {
+    let width = match *format {
+        TextFormat::Binary => 8,
+        TextFormat::Octal => 4,
+        TextFormat::Hex => 2,
+        _ => 0
+    };
+
+    let mut tokens = Vec::new();
+
+    for word in output.split_whitespace() {
+        let mut remaining = word;
+
+        while remaining.is_empty() == false {
+            if remaining.starts_with("..") {
+                let mut length = 2;
+
+                if remaining[2..].starts_with('{') {
+                    let end = remaining.find('}')?;
+                    let count = &remaining[3..end];
+
+                    if count.is_empty() || count.chars().all(|ch| ch.is_ascii_digit() || ch == ',') == false {
+                        return None;
+                    }
+
+                    length = end + 1;
+                }
+
+                tokens.push(remaining[..length].to_string());
+                remaining = &remaining[length..];
+
+                continue;
+            }
+
+            // decimal bytes have no fixed width and are separated by whitespace
+            let length = if width == 0 { remaining.len() } else { width };
+
+            if remaining.len() < length || remaining[..length].contains(['.', '{', '}', ',']) {
+                return None;
+            }
+
+            tokens.push(remaining[..length].to_string());
+            remaining = &remaining[length..];
+        }
+    }
+
+    Some(tokens)
+}
+
+/// Check if the value written in the format matches the digits of the token.
+fn matches_digits(token: &str, value: u8, format: &TextFormat) -> bool {
+    let digits = match *format {
+        TextFormat::Binary => format!("{:08b}", value),
+        TextFormat::Octal => format!("{:04o}", value),
+        TextFormat::Hex => format!("{:02X}", value),
+        _ => format!("{}", value)
+    };
+
+    digits.len() == token.len() && token.chars().zip(digits.chars()).all(|(expected, digit)| expected == '?' || expected.eq_ignore_ascii_case(&digit))
+}
+
+fn byte_class(values: &[u8]) -> Option<String> {
+    match values.len() {
+        0 => None,
+        1 => Some(format!("\\x{:02X}", values[0])),
+        256 => Some(".".to_string()),
+        _ => {
+            let mut class = "[".to_string();
+            let mut index = 0;
+
+            // join consecutive values into ranges
+            while index < values.len() {
+                let start = values[index];
+
+                while index + 1 < values.len() && values[index + 1] == values[index] + 1 {
+                    index += 1;
+                }
+
+                if values[index] == start {
+                    class.push_str(&format!("\\x{:02X}", start));
+                } else {
+                    class.push_str(&format!("\\x{:02X}-\\x{:02X}", start, values[index]));
+                }
+
+                index += 1;
+            }
+
+            class.push(']');
+
+            Some(class)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use regex::bytes::Regex;
+    use crate::utils::TextFormat;
+    use super::{compile, example};
+
+    fn is_match(output: &str, format: TextFormat, response: &[u8]) -> bool {
+        let pattern = compile(output, &format).unwrap();
+
+        Regex::new(&format!(r"\A(?:{})\z", pattern)).unwrap().is_match(response)
+    }
+
+    #[test]
+    fn match_wildcard_bytes() {
+        assert!(is_match("AA ?? 0d", TextFormat::Hex, b"\xAA\x41\x0D"));
+        assert!(is_match("A? ..{2} ff", TextFormat::Hex, b"\xA7\x00\x01\xFF"));
+        assert!(is_match("A?", TextFormat::Hex, b"\xB7") == false);
+        assert!(is_match("1111???? ..", TextFormat::Binary, b"\xF5\x0A"));
+        assert!(is_match("0101 ..", TextFormat::Octal, b"\x41\x00"));
+        assert!(is_match("65 1?? ..", TextFormat::Decimal, b"\x41\x7F\x00"));
+        assert!(is_match("65 1??", TextFormat::Decimal, b"\x41\x0F") == false);
+
+        assert_eq!(compile("AA.*", &TextFormat::Hex), None);
+        assert_eq!(compile("AA", &TextFormat::Text), None);
+    }
+
+    #[test]
+    fn generate_matching_bytes() {
+        assert_eq!(example("AA ?? ?1", &TextFormat::Hex), Some(b"\xAA\x00\x01".to_vec()));
+        assert_eq!(example("01 ..{3} .. ..{2,4}", &TextFormat::Hex), Some(b"\x01\x00\x00\x00\x00\x00\x00".to_vec()));
+        assert_eq!(example("65 1??", &TextFormat::Decimal), Some(b"\x41\x64".to_vec()));
+        assert_eq!(example("AA.*", &TextFormat::Hex), None);
+    }
+}
@@ -28,6 +28,16 @@
 
 pub mod test_case;
 pub mod test_suite;
+pub mod result;
+pub mod variables;
+pub mod assertion;
+pub mod script;
+mod partial_match;
+pub mod byte_pattern;
 
-pub use self::test_case::{TestCase, TestCaseSettings};
-pub use self::test_suite::{TestSuite, TestSuiteSettings};
+pub use self::test_case::{TestCase, TestCaseSettings, TestStep, MatchMode};
+pub use self::test_suite::{TestSuite, TestSuiteSettings, TestHook};
+pub use self::result::{TestResult, TestStatus, IterationResult, SuiteResult};
+pub use self::variables::Variables;
+pub use self::assertion::{Assertion, Comparison};
+pub use self::script::{Script, PortDeclaration};
@@ -0,0 +1,330 @@
+/*
+ * File: tests/partial_match.rs
+ * Date: 16.10.2026
+ * Author: agent
+ * 
+ * MIT License
+ * 
+ * Copyright (c) 2026 MarkAtk
+ * 
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+ * of the Software, and to permit persons to whom the Software is furnished to do
+ * so, subject to the following conditions:
+ * 
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ * 
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+use regex_syntax::ParserBuilder;
+use regex_syntax::hir::{Hir, HirKind, Literal, Class, Anchor, RepetitionKind, RepetitionRange};
+
+/// Maximum number of instructions before a pattern is considered too large for partial matching.
+const MAX_INSTRUCTIONS: usize = 100_000;
+
+#[derive(Debug, Clone)]
+enum Instruction {
+    Range(u8, u8, usize),
+    Split(usize, usize),
+    StartText(usize),
+    StartLine(usize),
+    Match
+}
+
+/// Regex telling if a response could still match once more data is received.
+///
+/// The pattern is always anchored at the start of the response. Assertions depending on following data,
+/// like the end of text or word boundaries, are assumed to hold so a response is never rejected too early.
+#[derive(Debug)]
+pub struct PartialRegex {
+    instructions: Vec<Instruction>,
+    start: usize
+}
+
+impl PartialRegex {
+    pub fn new(pattern: &str, case_insensitive: bool) -> Result<PartialRegex, String> {
+        let hir = match ParserBuilder::new().allow_invalid_utf8(true).case_insensitive(case_insensitive).build().parse(pattern) {
+            Ok(hir) => hir,
+            Err(e) => return Err(format!("{}", e))
+        };
+
+        let mut compiler = Compiler { instructions: vec!(Instruction::Match) };
+        let start = compiler.compile(&hir, 0)?;
+
+        Ok(PartialRegex { instructions: compiler.instructions, start })
+    }
+
+    /// Check if the subject is the start of a match, or a match itself.
+    pub fn could_match(&self, subject: &[u8]) -> bool {
+        let mut threads = Vec::new();
+        self.add_thread(&mut threads, self.start, None, true);
+
+        for &byte in subject.iter() {
+            let mut next_threads = Vec::new();
+
+            for &thread in threads.iter() {
+                if let Instruction::Range(start, end, next) = self.instructions[thread] {
+                    if start <= byte && byte <= end {
+                        self.add_thread(&mut next_threads, next, Some(byte), false);
+                    }
+                }
+            }
+
+            if next_threads.is_empty() {
+                return false;
+            }
+
+            threads = next_threads;
+        }
+
+        threads.is_empty() == false
+    }
+
+    /// Add the instruction and all instructions reachable without reading data.
+    fn add_thread(&self, threads: &mut Vec<usize>, instruction: usize, previous: Option<u8>, at_start: bool) {
+        let mut stack = vec!(instruction);
+
+        while let Some(instruction) = stack.pop() {
+            if threads.contains(&instruction) {
+                continue;
+            }
+
+            threads.push(instruction);
+
+            match self.instructions[instruction] {
+                Instruction::Split(first, second) => {
+                    stack.push(second);
+                    stack.push(first);
+                },
+                Instruction::StartText(next) if at_start => stack.push(next),
+                Instruction::StartLine(next) if at_start || previous == Some(b'\n') => stack.push(next),
+                _ => ()
+            };
+        }
+    }
+}
+
+/// Compiles a regex into instructions from the end to the start, each part continues with the given instruction.
+struct Compiler {
+    instructions: Vec<Instruction>
+}
+
+impl Compiler {
+    fn compile(&mut self, hir: &Hir, next: usize) -> Result<usize, String> {
+        if self.instructions.len() > MAX_INSTRUCTIONS {
+            return Err("Pattern too large".to_string());
+        }
+
+        match *hir.kind() {
+            HirKind::Empty => Ok(next),
+            HirKind::Literal(Literal::Unicode(ch)) => {
+                let mut buffer = [0; 4];
+                let bytes: Vec<(u8, u8)> = ch.encode_utf8(&mut buffer).bytes().map(|byte| (byte, byte)).collect();
+
+                Ok(self.push_sequence(&bytes, next))
+            },
+            HirKind::Literal(Literal::Byte(byte)) => Ok(self.push(Instruction::Range(byte, byte, next))),
+            HirKind::Class(Class::Unicode(ref class)) => {
+                let mut sequences = Vec::new();
+
+                for range in class.iter() {
+                    utf8_sequences(range.start() as u32, range.end() as u32, &mut sequences);
+                }
+
+                let entries: Vec<usize> = sequences.iter().map(|sequence| self.push_sequence(sequence, next)).collect();
+
+                Ok(self.push_alternation(&entries, next))
+            },
+            HirKind::Class(Class::Bytes(ref class)) => {
+                let entries: Vec<usize> = class.iter().map(|range| self.push(Instruction::Range(range.start(), range.end(), next))).collect();
+
+                Ok(self.push_alternation(&entries, next))
+            },
+            HirKind::Anchor(Anchor::StartText) => Ok(self.push(Instruction::StartText(next))),
+            HirKind::Anchor(Anchor::StartLine) => Ok(self.push(Instruction::StartLine(next))),
+            HirKind::Anchor(_) | HirKind::WordBoundary(_) => Ok(next),
+            HirKind::Group(ref group) => self.compile(&group.hir, next),
+            HirKind::Concat(ref hirs) => {
+                let mut next = next;
+
+                for hir in hirs.iter().rev() {
+                    next = self.compile(hir, next)?;
+                }
+
+                Ok(next)
+            },
+            HirKind::Alternation(ref hirs) => {
+                let mut entries = Vec::new();
+
+                for hir in hirs.iter() {
+                    entries.push(self.compile(hir, next)?);
+                }
+
+                Ok(self.push_alternation(&entries, next))
+            },
+            HirKind::Repetition(ref repetition) => {
+                let (min, max) = match repetition.kind {
+                    RepetitionKind::ZeroOrOne => (0, Some(1)),
+                    RepetitionKind::ZeroOrMore => (0, None),
+                    RepetitionKind::OneOrMore => (1, None),
+                    RepetitionKind::Range(RepetitionRange::Exactly(count)) => (count, Some(count)),
+                    RepetitionKind::Range(RepetitionRange::AtLeast(min)) => (min, None),
+                    RepetitionKind::Range(RepetitionRange::Bounded(min, max)) => (min, Some(max))
+                };
+
+                self.compile_repetition(&repetition.hir, min, max, next)
+            }
+        }
+    }
+
+    fn compile_repetition(&mut self, hir: &Hir, min: u32, max: Option<u32>, next: usize) -> Result<usize, String> {
+        // optional or unbounded repetitions follow the required ones
+        let mut next = match max {
+            Some(max) => {
+                let mut next = next;
+
+                for _ in min..max {
+                    let entry = self.compile(hir, next)?;
+                    next = self.push(Instruction::Split(entry, next));
+                }
+
+                next
+            },
+            None => {
+                let split = self.push(Instruction::Split(next, next));
+                let entry = self.compile(hir, split)?;
+                self.instructions[split] = Instruction::Split(entry, next);
+
+                split
+            }
+        };
+
+        for _ in 0..min {
+            next = self.compile(hir, next)?;
+        }
+
+        Ok(next)
+    }
+
+    fn push(&mut self, instruction: Instruction) -> usize {
+        self.instructions.push(instruction);
+
+        self.instructions.len() - 1
+    }
+
+    fn push_sequence(&mut self, ranges: &[(u8, u8)], next: usize) -> usize {
+        let mut next = next;
+
+        for &(start, end) in ranges.iter().rev() {
+            next = self.push(Instruction::Range(start, end, next));
+        }
+
+        next
+    }
+
+    fn push_alternation(&mut self, entries: &[usize], next: usize) -> usize {
+        let mut iterator = entries.iter().rev();
+
+        let mut entry = match iterator.next() {
+            Some(&entry) => entry,
+            None => return next
+        };
+
+        for &other in iterator {
+            entry = self.push(Instruction::Split(other, entry));
+        }
+
+        entry
+    }
+}
+
+/// Split a range of characters into sequences of byte ranges matching their UTF-8 encoding.
+fn utf8_sequences(start: u32, end: u32, sequences: &mut Vec<Vec<(u8, u8)>>) {
+    let mut stack = vec!((start, end));
+
+    'ranges: while let Some((start, end)) = stack.pop() {
+        // surrogates can not be encoded
+        if start <= 0xDFFF && end >= 0xD800 {
+            if start < 0xD800 {
+                stack.push((start, 0xD7FF));
+            }
+
+            if end > 0xDFFF {
+                stack.push((0xE000, end));
+            }
+
+            continue;
+        }
+
+        // all characters must have the same encoded length
+        for &max in [0x7F, 0x7FF, 0xFFFF].iter() {
+            if start <= max && max < end {
+                stack.push((start, max));
+                stack.push((max + 1, end));
+
+                continue 'ranges;
+            }
+        }
+
+        // continuation bytes must span their full range except for the last one
+        for shift in 1..4 {
+            let mask = (1u32 << (6 * shift)) - 1;
+
+            if start & !mask != end & !mask {
+                if start & mask != 0 {
+                    stack.push((start, start | mask));
+                    stack.push(((start | mask) + 1, end));
+
+                    continue 'ranges;
+                }
+
+                if end & mask != mask {
+                    stack.push((start, (end & !mask) - 1));
+                    stack.push((end & !mask, end));
+
+                    continue 'ranges;
+                }
+            }
+        }
+
+        let mut start_buffer = [0; 4];
+        let mut end_buffer = [0; 4];
+        let start_bytes = std::char::from_u32(start).unwrap().encode_utf8(&mut start_buffer).as_bytes();
+        let end_bytes = std::char::from_u32(end).unwrap().encode_utf8(&mut end_buffer).as_bytes();
+
+        sequences.push(start_bytes.iter().cloned().zip(end_bytes.iter().cloned()).collect());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PartialRegex;
+
+    #[test]
+    fn match_partial_responses() {
+        let regex = PartialRegex::new(r"\A(?:OK [0-9]+ (?P<unit>mV|V)\r\n)\z", false).unwrap();
+
+        assert!(regex.could_match(b""));
+        assert!(regex.could_match(b"OK 12"));
+        assert!(regex.could_match(b"OK 12 m"));
+        assert!(regex.could_match(b"OK 12 V\r\n"));
+        assert!(regex.could_match(b"OK x") == false);
+        assert!(regex.could_match(b"OK 12 A") == false);
+
+        let regex = PartialRegex::new("(?i)temp: [0-9,]+°C", false).unwrap();
+
+        assert!(regex.could_match("TEMP: 21,5".as_bytes()));
+        assert!(regex.could_match(&"temp: 21,5°C".as_bytes()[..11]));
+        assert!(regex.could_match("temp: 21,5°F".as_bytes()) == false);
+    }
+}
@@ -0,0 +1,205 @@
+/*
+ * File: src/tests/result.rs
+ * Date: 15.10.2026
+ * Author: agent
+ * 
+ * MIT License
+ * 
+ * Copyright (c) 2026 MarkAtk
+ * 
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+ * of the Software, and to permit persons to whom the Software is furnished to do
+ * so, subject to the following conditions:
+ * 
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ * 
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+use std::time::Duration;
+use serde::{Serialize, Serializer};
+use serde::ser::SerializeStruct;
+use crate::tests::test_suite::TestHook;
+use crate::utils::{self, TextFormat};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TestStatus {
+    Passed,
+    Failed,
+    Errored,
+    AllowedFailure,
+    Skipped
+}
+
+/// Result of a single execution of a test, tests with `repeat` set are executed multiple times.
+#[derive(Debug, Clone)]
+pub struct IterationResult {
+    pub response: Vec<u8>,
+    pub successful: bool,
+    pub duration: Duration
+}
+
+#[derive(Debug, Clone)]
+pub struct TestResult {
+    pub name: String,
+    pub input: String,
+    pub expected: String,
+    pub output_format: TextFormat,
+    pub response: Option<Vec<u8>>,
+    pub status: TestStatus,
+    pub error: Option<String>,
+    /// Number of the step which failed, only set for tests with multiple steps.
+    pub step: Option<usize>,
+    pub repeat: u32,
+    pub iterations: Vec<IterationResult>,
+    pub duration: Duration,
+    /// Set if the test was run as a hook of its group.
+    pub hook: Option<TestHook>,
+    /// Message of the first assertion not met, e.g. `voltage: expected 3.3±0.1, got 3.52`.
+    pub assertion: Option<String>,
+    /// Set if no response was received in time.
+    pub timed_out: bool
+}
+
+impl TestResult {
+    /// Check if the test passed or was allowed to fail.
+    pub fn is_successful(&self) -> bool {
+        self.status == TestStatus::Passed || self.status == TestStatus::AllowedFailure
+    }
+
+    /// Get the response in the output format of the test.
+    pub fn response_text(&self) -> Option<String> {
+        self.response.as_ref().map(|response| response_string(response, &self.output_format))
+    }
+
+    /// Get the reason the test did not pass, if any.
+    pub fn failure_message(&self) -> Option<String> {
+        let message = self.failure_reason()?;
+
+        if let Some(step) = self.step {
+            Some(format!("step {}: {}", step, message))
+        } else {
+            Some(message)
+        }
+    }
+
+    fn failure_reason(&self) -> Option<String> {
+        if let Some(ref err) = self.error {
+            return Some(err.clone());
+        }
+
+        if let Some(ref assertion) = self.assertion {
+            return Some(assertion.clone());
+        }
+
+        match self.status {
+            TestStatus::Failed | TestStatus::AllowedFailure => {
+                if let Some(response) = self.response_text() {
+                    Some(format!("expected '{}' but received '{}'", self.expected, response))
+                } else {
+                    Some(format!("expected '{}' but received nothing", self.expected))
+                }
+            },
+            TestStatus::Skipped => Some("Not run".to_string()),
+            _ => None
+        }
+    }
+}
+
+impl Serialize for TestResult {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let iterations: Vec<Iteration> = self.iterations.iter().map(|iteration| Iteration {
+            response: response_string(&iteration.response, &self.output_format),
+            successful: iteration.successful,
+            duration: seconds(iteration.duration)
+        }).collect();
+
+        let mut state = serializer.serialize_struct("TestResult", 13)?;
+        state.serialize_field("name", &self.name)?;
+        state.serialize_field("input", &self.input)?;
+        state.serialize_field("expected", &self.expected)?;
+        state.serialize_field("response", &self.response_text())?;
+        state.serialize_field("status", &self.status)?;
+        state.serialize_field("error", &self.error)?;
+        state.serialize_field("step", &self.step)?;
+        state.serialize_field("repeat", &self.repeat)?;
+        state.serialize_field("iterations", &iterations)?;
+        state.serialize_field("duration", &seconds(self.duration))?;
+        state.serialize_field("hook", &self.hook)?;
+        state.serialize_field("assertion", &self.assertion)?;
+        state.serialize_field("timed_out", &self.timed_out)?;
+        state.end()
+    }
+}
+
+#[derive(Serialize)]
+struct Iteration {
+    response: String,
+    successful: bool,
+    duration: f64
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SuiteResult {
+    pub name: String,
+    pub tests: Vec<TestResult>,
+    /// Results of all hooks run, in order of execution.
+    pub hooks: Vec<TestResult>,
+    /// Set if the suite stopped early because of `stop-on-failure`.
+    #[serde(skip)]
+    pub stopped: bool
+}
+
+impl SuiteResult {
+    pub fn duration(&self) -> Duration {
+        self.tests.iter().chain(self.hooks.iter()).map(|test| test.duration).sum()
+    }
+
+    pub fn count(&self, status: TestStatus) -> usize {
+        self.tests.iter().filter(|test| test.status == status).count()
+    }
+
+    /// Get the number of tests passed or allowed to fail.
+    pub fn successful(&self) -> usize {
+        self.count(TestStatus::Passed) + self.count(TestStatus::AllowedFailure)
+    }
+
+    /// Get the number of tests and hooks failed or stopped by an error.
+    pub fn failed(&self) -> usize {
+        self.count(TestStatus::Failed) + self.count(TestStatus::Errored) + self.failed_hooks().count()
+    }
+
+    /// Get the number of tests and hooks which did not pass because no response was received in time.
+    pub fn timed_out(&self) -> usize {
+        self.tests.iter().chain(self.hooks.iter())
+            .filter(|test| test.timed_out && test.is_successful() == false)
+            .count()
+    }
+
+    /// Get all hooks which did not pass.
+    pub fn failed_hooks(&self) -> impl Iterator<Item = &TestResult> {
+        self.hooks.iter().filter(|hook| hook.is_successful() == false)
+    }
+}
+
+pub fn seconds(duration: Duration) -> f64 {
+    duration.as_secs() as f64 + f64::from(duration.subsec_nanos()) / 1_000_000_000.0
+}
+
+fn response_string(response: &[u8], format: &TextFormat) -> String {
+    match format {
+        TextFormat::Text => utils::escape_bytes(response),
+        _ => utils::radix_string(response, format)
+    }
+}
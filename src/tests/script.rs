@@ -0,0 +1,63 @@
+/*
+ * File: src/tests/script.rs
+ * Date: 16.10.2026
+ * Author: agent
+ * 
+ * MIT License
+ * 
+ * Copyright (c) 2026 MarkAtk
+ * 
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+ * of the Software, and to permit persons to whom the Software is furnished to do
+ * so, subject to the following conditions:
+ * 
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ * 
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+use crate::serial::settings::PartialSettings;
+use crate::tests::TestSuite;
+
+/// Port declared with `[@port name]` and its serial settings.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PortDeclaration {
+    pub name: String,
+    pub settings: PartialSettings
+}
+
+/// Parsed script with the ports it declares and its test suites.
+#[derive(Debug, Clone)]
+pub struct Script {
+    /// Serial settings of the default port set with `[@port setting=value, ...]`.
+    pub settings: PartialSettings,
+    /// All declared ports, in order of declaration.
+    pub ports: Vec<PortDeclaration>,
+    pub test_suites: Vec<TestSuite>
+}
+
+impl Script {
+    /// Get the declared port with the name.
+    pub fn port(&self, name: &str) -> Option<&PortDeclaration> {
+        self.ports.iter().find(|port| port.name == name)
+    }
+
+    /// Check if any test or hook runs on the default port.
+    pub fn uses_default_port(&self) -> bool {
+        self.test_suites.iter().any(|test_suite| {
+            test_suite.tests().iter()
+                .chain(test_suite.hooks().iter().map(|(_, test)| test))
+                .any(|test| test.settings.port.is_none())
+        })
+    }
+}
@@ -26,23 +26,33 @@
  * SOFTWARE.
  */
 
+use std::fmt;
 use std::io;
-use std::str;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use std::thread::sleep;
 use colored::*;
-use regex::Regex;
-use crate::serial::Serial;
+use regex::bytes::{Regex, RegexBuilder};
+use crate::serial::{Serial, Framing, PortRegistry};
+use crate::tests::result::{TestResult, TestStatus, IterationResult};
+use crate::tests::variables::{self, Variables};
+use crate::tests::partial_match::PartialRegex;
+use crate::tests::assertion::Assertion;
+use crate::tests::byte_pattern;
 use crate::utils;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct TestCaseSettings {
     pub ignore_case: Option<bool>,
     pub repeat: Option<u32>,
     pub delay: Option<Duration>,
     pub timeout: Option<Duration>,
     pub allow_failure: Option<bool>,
-    pub verbose: Option<bool>
+    pub verbose: Option<bool>,
+    pub match_mode: Option<MatchMode>,
+    pub framing: Option<Framing>,
+    pub assertions: Vec<Assertion>,
+    /// Name of the port the test runs on, the default port is used if not set.
+    pub port: Option<String>
 }
 
 impl TestCaseSettings {
@@ -70,23 +80,120 @@ impl TestCaseSettings {
         if self.verbose.is_none() && other.verbose.is_some() {
             self.verbose = other.verbose;
         }
+
+        if self.match_mode.is_none() && other.match_mode.is_some() {
+            self.match_mode = other.match_mode;
+        }
+
+        if self.framing.is_none() && other.framing.is_some() {
+            self.framing = other.framing.clone();
+        }
+
+        if self.assertions.is_empty() {
+            self.assertions = other.assertions.clone();
+        }
+
+        if self.port.is_none() && other.port.is_some() {
+            self.port = other.port.clone();
+        }
     }
 }
 
-impl Default for TestCaseSettings {
-    fn default() -> TestCaseSettings {
-        TestCaseSettings {
-            ignore_case: None,
-            repeat: None,
-            delay: None,
-            timeout: None,
-            allow_failure: None,
-            verbose: None
+
+/// How the expected output is matched against a response.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum MatchMode {
+    /// The regex matches the whole response.
+    #[default]
+    Exact,
+    /// The response contains the output literally.
+    Contains,
+    /// The response starts with the output literally.
+    StartsWith,
+    /// The response ends with the output literally.
+    EndsWith,
+    /// The regex matches any whole line of the response.
+    Line,
+    /// The regex matches anywhere in the response.
+    RegexSearch
+}
+
+impl MatchMode {
+    /// Get the regex pattern matching responses in this mode.
+    pub fn pattern(&self, output: &str) -> String {
+        if self.is_regex() {
+            self.regex_pattern(output)
+        } else {
+            self.regex_pattern(&regex::escape(output))
         }
     }
+
+    /// Get the regex pattern matching responses in this mode for an output already being a regex.
+    pub fn regex_pattern(&self, regex: &str) -> String {
+        match *self {
+            MatchMode::Exact => format!(r"\A(?:{})\z", regex),
+            MatchMode::Contains | MatchMode::RegexSearch => regex.to_string(),
+            MatchMode::StartsWith => format!(r"\A(?:{})", regex),
+            MatchMode::EndsWith => format!(r"(?:{})\z", regex),
+            MatchMode::Line => format!(r"(?m:^)(?:{})(?m:\r?$)", regex)
+        }
+    }
+
+    /// Check if matches must start at the beginning of the response.
+    pub fn is_anchored(&self) -> bool {
+        matches!(*self, MatchMode::Exact | MatchMode::StartsWith)
+    }
+
+    /// Check if the output is interpreted as regex in this mode.
+    pub fn is_regex(&self) -> bool {
+        matches!(*self, MatchMode::Exact | MatchMode::Line | MatchMode::RegexSearch)
+    }
+}
+
+
+impl fmt::Display for MatchMode {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let name = match *self {
+            MatchMode::Exact => "exact",
+            MatchMode::Contains => "contains",
+            MatchMode::StartsWith => "starts-with",
+            MatchMode::EndsWith => "ends-with",
+            MatchMode::Line => "line",
+            MatchMode::RegexSearch => "regex-search"
+        };
+
+        formatter.write_str(name)
+    }
+}
+
+/// Single step of a test, tests with one input and output consist of one send and one expect step.
+#[derive(Debug, Clone)]
+pub enum TestStep {
+    /// Write the content to the serial port.
+    Send(String, utils::TextFormat),
+    /// Read a response matching the content as regex.
+    Expect(String, utils::TextFormat)
 }
 
-#[derive(Debug)]
+impl TestStep {
+    /// Get the bytes of the content taken literally, without interpreting it as regex.
+    pub fn bytes(&self) -> Result<Vec<u8>, String> {
+        match *self {
+            TestStep::Send(ref content, ref format) | TestStep::Expect(ref content, ref format) => content_bytes(content, format)
+        }
+    }
+}
+
+impl ToString for TestStep {
+    fn to_string(&self) -> String {
+        match *self {
+            TestStep::Send(ref content, _) => format!("send \"{}\"", content),
+            TestStep::Expect(ref content, _) => format!("expect \"{}\"", content)
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct TestCase {
     pub settings: TestCaseSettings,
     pub input_format: utils::TextFormat,
@@ -95,9 +202,9 @@ pub struct TestCase {
     name: String,
     input: String,
     output: String,
-    response: Option<String>,
-    successful: Option<bool>,
-    error: Option<String>
+    sends_input: bool,
+    expects_output: bool,
+    steps: Option<Vec<TestStep>>
 }
 
 impl TestCase {
@@ -106,133 +213,407 @@ impl TestCase {
             name,
             input,
             output,
+            sends_input: true,
+            expects_output: true,
+            steps: None,
+            settings: Default::default(),
+            input_format: utils::TextFormat::Text,
+            output_format: utils::TextFormat::Text
+        }
+    }
+
+    /// Create a test only sending the input without waiting for a response.
+    pub fn new_send(name: String, input: String) -> TestCase {
+        let mut test = TestCase::new(name, input, String::new());
+        test.expects_output = false;
+
+        test
+    }
+
+    /// Create a test only waiting for output without sending anything, e.g. for unsolicited messages.
+    pub fn new_expect(name: String, output: String) -> TestCase {
+        let mut test = TestCase::new(name, String::new(), output);
+        test.sends_input = false;
+
+        test
+    }
+
+    /// Create a test running the steps in order, failing at the first expectation not met.
+    pub fn new_sequence(name: String, steps: Vec<TestStep>) -> TestCase {
+        TestCase {
+            name,
+            input: String::new(),
+            output: String::new(),
+            sends_input: true,
+            expects_output: true,
+            steps: Some(steps),
             settings: Default::default(),
             input_format: utils::TextFormat::Text,
-            output_format: utils::TextFormat::Text,
+            output_format: utils::TextFormat::Text
+        }
+    }
+
+    /// Run the test on the port set in the settings, or the default port of the registry.
+    pub fn run(&self, ports: &mut PortRegistry) -> TestResult {
+        self.run_with_variables(ports, &mut Variables::new())
+    }
+
+    /// Run the test with all `${name}` references resolved from the variables. Values of named capture groups
+    /// in expected outputs are stored in the variables once they match.
+    pub fn run_with_variables(&self, ports: &mut PortRegistry, variables: &mut Variables) -> TestResult {
+        let mut result = self.skip();
+
+        let start = Instant::now();
+        let outcome = match ports.get(self.settings.port.as_deref()) {
+            Ok(serial) => self.execute(serial, &mut result, variables),
+            Err(e) => Err(e)
+        };
+
+        result.duration = start.elapsed();
+        result.response = result.iterations.last().map(|iteration| iteration.response.clone());
+
+        let allow_failure = self.settings.allow_failure.unwrap_or(false);
+
+        result.status = match outcome {
+            Ok(true) => TestStatus::Passed,
+            Ok(false) if allow_failure => TestStatus::AllowedFailure,
+            Ok(false) => TestStatus::Failed,
+            Err(err) => {
+                result.error = Some(err);
+
+                if allow_failure { TestStatus::AllowedFailure } else { TestStatus::Errored }
+            }
+        };
+
+        result
+    }
+
+    /// Get the result of the test without running it.
+    pub fn skip(&self) -> TestResult {
+        TestResult {
+            name: self.name.clone(),
+            input: self.input.clone(),
+            expected: self.output.clone(),
+            output_format: self.output_format,
             response: None,
-            successful: None,
-            error: None
+            status: TestStatus::Skipped,
+            error: None,
+            step: None,
+            repeat: self.settings.repeat.unwrap_or(0),
+            iterations: Vec::new(),
+            duration: Duration::from_secs(0),
+            hook: None,
+            assertion: None,
+            timed_out: false
         }
     }
 
-    pub fn run(&mut self, serial: &mut Serial) -> Result<(), String> {
-        // get input and desired output in correct format
-        let input: String;
-        let mut output: String;
+    pub fn name(&self) -> &str {
+        &self.name
+    }
 
-        if self.input_format == utils::TextFormat::Text {
-            input = self.descape_string(&self.input);
-        } else {
-            input = self.input.clone();
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+
+    pub fn output(&self) -> &str {
+        &self.output
+    }
+
+    /// Get all steps of the test in order of execution.
+    pub fn steps(&self) -> Vec<TestStep> {
+        match self.steps {
+            Some(ref steps) => steps.clone(),
+            None => {
+                let mut steps = Vec::new();
+
+                if self.sends_input {
+                    steps.push(TestStep::Send(self.input.clone(), self.input_format));
+                }
+
+                if self.expects_output {
+                    steps.push(TestStep::Expect(self.output.clone(), self.output_format));
+                }
+
+                steps
+            }
         }
+    }
+
+    /// Check if the test was created from a list of steps instead of one input and output.
+    pub fn is_sequence(&self) -> bool {
+        self.steps.is_some()
+    }
+
+    pub fn push_step(&mut self, step: TestStep) {
+        if let Some(ref mut steps) = self.steps {
+            steps.push(step);
+        }
+    }
 
-        if self.output_format == utils::TextFormat::Text {
-            output = self.descape_string(&self.output);
+    /// Get the bytes written to the serial port when running the test.
+    pub fn input_bytes(&self) -> Result<Vec<u8>, String> {
+        content_bytes(&self.input, &self.input_format)
+    }
+
+    /// Get the bytes of the output taken literally, without interpreting it as regex.
+    pub fn output_bytes(&self) -> Result<Vec<u8>, String> {
+        content_bytes(&self.output, &self.output_format)
+    }
+
+    /// Get the printable description of a result of this test.
+    pub fn format_result(&self, result: &TestResult) -> String {
+        let at_step = if let Some(step) = result.step {
+            format!(" at step {}", step)
         } else {
-            output = self.output.clone();
+            String::new()
+        };
+
+        if let Some(ref err) = result.error {
+            return format!("{}...{}{}: {}", self.title(), "Error".red(), at_step, err);
+        }
+
+        if result.status == TestStatus::Failed {
+            if let Some(ref assertion) = result.assertion {
+                return format!("{}...{}, {}", self.title(), "Failed".red(), assertion);
+            }
+
+            return if let Some(response) = result.response_text() {
+                format!("{}...{}{}, expected '{}' but received '{}'", self.title(), "Failed".red(), at_step, result.expected, response)
+            } else {
+                format!("{}...{}{}, expected '{}' but received nothing", self.title(), "Failed".red(), at_step, result.expected)
+            };
         }
 
-        if self.settings.ignore_case.unwrap_or(false) {
-            output = output.to_lowercase();
+        if result.status == TestStatus::Skipped {
+            return self.title();
         }
 
-        let regex = match Regex::new(&output) {
-            Ok(regex) => regex,
-            Err(_) => return self.exit_run_with_error(format!("Error in regex"))
+        // test passed
+        let repeat = if let Some(count) = self.settings.repeat {
+            format!(" ({}x)", count)
+        } else {
+            String::new()
+        };
+
+        let verbose = if self.settings.verbose.unwrap_or(false) {
+            if let Some(response) = result.response_text() {
+                format!(", response: '{}'", response)
+            } else {
+                ", no response".to_string()
+            }
+        } else {
+            String::new()
+        };
+
+        let status = if result.status == TestStatus::Passed {
+            format!("{}", "OK".green())
+        } else {
+            format!("{}{} (failed)", "OK".yellow(), at_step)
         };
 
+        format!("{}...{}{}{}", self.title(), status, repeat, verbose)
+    }
+
+    fn execute(&self, serial: &mut Serial, result: &mut TestResult, variables: &mut Variables) -> Result<bool, String> {
+        let steps = self.steps();
+
         // run test repeat + 1 times
         let mut repeat = 1;
-        let mut success: bool = false;
 
         if let Some(count) = self.settings.repeat {
             repeat += count;
         }
 
         for _ in 0..repeat {
+            let start = Instant::now();
+
             // if delay is set wait before execution
             if let Some(delay) = self.settings.delay {
                 sleep(delay);
             }
 
-            match serial.write_format(&input, &self.input_format) {
-                Ok(_) => (),
-                Err(e) => return self.exit_run_with_error(format!("Unable to write to serial port: {}", e))
-            };
+            let mut response = Vec::new();
+            let mut successful = true;
 
-            let response = match self.read_response(serial) {
-                Ok(res) => res,
-                Err(err) => return self.exit_run_with_error(err)
-            };
+            for (index, step) in steps.iter().enumerate() {
+                // failing steps are only reported for tests with multiple steps
+                if self.is_sequence() {
+                    result.step = Some(index + 1);
+                }
 
-            // check if response is correct
-            if let Some(mat) = regex.find(&response) {
-                success = mat.start() == 0 && mat.end() == response.len();
-            } else {
-                success = false;
+                match *step {
+                    TestStep::Send(ref content, ref format) => {
+                        result.input = self.resolve(content, format, variables, false)?;
+
+                        if let Err(e) = serial.write_format(&descape_content(&result.input, format), format) {
+                            return Err(format!("Unable to write to serial port: {}", e));
+                        }
+                    },
+                    TestStep::Expect(ref content, ref format) => {
+                        let match_mode = self.settings.match_mode.unwrap_or_default();
+
+                        result.expected = self.resolve(content, format, variables, match_mode.is_regex())?;
+                        result.output_format = *format;
+
+                        // checksums of expected frames are computed before matching
+                        let output = utils::checksum::expand_helpers(&descape_content(&result.expected, format), format)?;
+                        let ignore_case = self.settings.ignore_case.unwrap_or(false);
+
+                        // outputs written as bytes are matched on the raw response instead of its radix string
+                        let (pattern, subject_format) = match byte_pattern::compile(&output, format) {
+                            Some(bytes) => (match_mode.regex_pattern(&bytes), utils::TextFormat::Text),
+                            None => (match_mode.pattern(&output), *format)
+                        };
+
+                        let regex = match RegexBuilder::new(&pattern).case_insensitive(ignore_case).build() {
+                            Ok(regex) => regex,
+                            Err(_) => return Err("Error in regex".to_string())
+                        };
+
+                        // responses can only be rejected early if matches must start at the beginning
+                        let partial = if match_mode.is_anchored() {
+                            PartialRegex::new(&pattern, ignore_case).ok()
+                        } else {
+                            None
+                        };
+
+                        response = match self.read_response(serial, &subject_format, &regex, partial.as_ref()) {
+                            Ok(response) => response,
+                            Err(ref e) if e.kind() == io::ErrorKind::TimedOut => {
+                                result.timed_out = true;
+
+                                return Err(e.to_string());
+                            },
+                            Err(e) => return Err(format!("Error while running test {}", e))
+                        };
+                        let subject = match_subject(&response, &subject_format);
+
+                        // check if response is correct
+                        successful = regex.is_match(&subject);
+
+                        if successful == false {
+                            break;
+                        }
+
+                        self.capture(&regex, &subject, variables);
+                    }
+                };
             }
 
-            self.response = Some(response);
+            // assertions are checked on the values captured by all steps
+            if successful && self.settings.assertions.is_empty() == false {
+                result.step = None;
+
+                for assertion in self.settings.assertions.iter() {
+                    if let Some(message) = assertion.check(variables)? {
+                        result.assertion = Some(message);
+                        successful = false;
 
-            if success == false {
-                break;
+                        break;
+                    }
+                }
+            }
+
+            result.iterations.push(IterationResult {
+                response,
+                successful,
+                duration: start.elapsed()
+            });
+
+            if successful == false {
+                return Ok(false);
             }
         }
 
-        self.successful = Some(success);
+        result.step = None;
 
-        Ok(())
+        Ok(true)
     }
 
-    pub fn is_successful(&self) -> Option<bool> {
-        self.successful
+    /// Get the content with all variables replaced by their values, values in patterns are matched literally.
+    fn resolve(&self, content: &str, format: &utils::TextFormat, variables: &Variables, pattern: bool) -> Result<String, String> {
+        variables::interpolate(content, |name| {
+            let mut value = variables::lookup(variables, name)?;
+
+            if pattern {
+                value = regex::escape(&value);
+            }
+
+            Ok(self.escape_value(&value, format))
+        })
     }
 
-    pub fn error(&self) -> Option<String> {
-        self.error.clone()
+    /// Escape a variable value so it is kept unchanged when the content is descaped.
+    fn escape_value(&self, value: &str, format: &utils::TextFormat) -> String {
+        if *format == utils::TextFormat::Text {
+            value.replace('\\', "\\\\")
+        } else {
+            value.to_string()
+        }
     }
 
-    fn read_response(&mut self, serial: &mut Serial) -> Result<String, String> {
-        let mut response = String::new();
+    /// Store the values of all named capture groups of the output in the variables.
+    fn capture(&self, regex: &Regex, subject: &[u8], variables: &mut Variables) {
+        let captures = match regex.captures(subject) {
+            Some(captures) => captures,
+            None => return
+        };
 
-        loop {
-            let response_chunk;
+        for name in regex.capture_names().flatten() {
+            if let Some(value) = captures.name(name) {
+                variables.insert(name.to_string(), String::from_utf8_lossy(value.as_bytes()).to_string());
+            }
+        }
+    }
+
+    fn read_response(&self, serial: &mut Serial, format: &utils::TextFormat, regex: &Regex, partial: Option<&PartialRegex>) -> Result<Vec<u8>, io::Error> {
+        let mut response = Vec::new();
 
-            if let Some(timeout) = self.settings.timeout {
-                response_chunk = serial.read_with_timeout(timeout);
+        // responses with explicit framing are read until their end regardless of the output
+        if let Some(ref framing) = self.settings.framing {
+            return if let Some(timeout) = self.settings.timeout {
+                serial.read_frame_with_timeout(framing, timeout)
             } else {
-                response_chunk = serial.read();
-            }
+                serial.read_frame(framing)
+            };
+        }
 
-            match response_chunk {
-                Ok(bytes) => {
-                    let mut new_text = match self.output_format {
-                        utils::TextFormat::Text => str::from_utf8(bytes).unwrap().to_string(),
-                        _ => utils::radix_string(bytes, &self.output_format)
-                    };
+        // the whole response must arrive within the timeout, devices may keep sending data never matching
+        let timeout = self.settings.timeout.unwrap_or_else(|| serial.timeout());
+        let start = Instant::now();
 
-                    if self.settings.ignore_case.unwrap_or(false) {
-                        new_text = new_text.to_lowercase();
-                    }
+        loop {
+            let remaining = match timeout.checked_sub(start.elapsed()) {
+                Some(remaining) if remaining > Duration::from_millis(0) => remaining,
+                _ => return Err(io::Error::new(io::ErrorKind::TimedOut, format!("No matching response within {}ms", timeout.as_millis())))
+            };
 
-                    response.push_str(new_text.as_str());
+            match serial.read_with_timeout(remaining) {
+                Ok(bytes) => {
+                    response.extend_from_slice(bytes);
 
-                    if self.output == response {
+                    let subject = match_subject(&response, format);
+
+                    // stop as soon as the response matches or can not match anymore
+                    if regex.is_match(&subject) {
                         break;
                     }
 
-                    if self.output.starts_with(response.as_str()) == false {
-                        break;
+                    if let Some(partial) = partial {
+                        if partial.could_match(&subject) == false {
+                            break;
+                        }
                     }
                 },
                 Err(ref e) if e.kind() == io::ErrorKind::TimedOut => {
                     if response.len() == 0 {
-                        return Err("Connection timed out".to_string());
+                        return Err(io::Error::new(io::ErrorKind::TimedOut, "Connection timed out"));
                     }
 
                     break;
                 },
-                Err(e) => return Err(format!("Error while running test {}", e))
+                Err(e) => return Err(e)
             }
         }
 
@@ -240,86 +621,255 @@ impl TestCase {
     }
 
     fn title(&self) -> String {
+        if let Some(ref steps) = self.steps {
+            return if self.name.is_empty() {
+                format!("({} steps)", steps.len())
+            } else {
+                format!("{} ({} steps)", self.name, steps.len())
+            };
+        }
+
+        // tests only expecting output are shown as written in scripts
+        if self.sends_input == false {
+            return if self.name.is_empty() {
+                format!(": {}", self.output)
+            } else {
+                format!("{} : \"{}\"", self.name, self.output)
+            };
+        }
+
         if self.name != "" {
             format!("{} \"{}\"", self.name, self.input)
         } else {
             self.input.clone()
         }
     }
+}
+
+impl ToString for TestCase {
+    fn to_string(&self) -> String {
+        self.title()
+    }
+}
 
-    fn descape_string(&self, text: &str) -> String {
-        let mut response = String::new();
-        let mut descape_next_char = false;
-        let mut iterator = text.chars();
+/// Get the bytes the output pattern is matched against, responses in radix formats are matched as radix string.
+fn match_subject(response: &[u8], format: &utils::TextFormat) -> Vec<u8> {
+    match *format {
+        utils::TextFormat::Text => response.to_vec(),
+        _ => utils::radix_string(response, format).into_bytes()
+    }
+}
 
-        loop {
-            match iterator.next() {
-                Some('t') if descape_next_char => response.push('\t'),
-                Some('r') if descape_next_char => response.push('\r'),
-                Some('n') if descape_next_char => response.push('\n'),
-                Some('\\') if descape_next_char == false => {
-                    descape_next_char = true;
-
-                    continue;
-                },
-                Some(ch) => response.push(ch),
-                None => break
-            };
+/// Replace escape sequences of content in text format.
+fn descape_content(content: &str, format: &utils::TextFormat) -> String {
+    if *format == utils::TextFormat::Text {
+        descape_string(content)
+    } else {
+        content.to_string()
+    }
+}
 
-            descape_next_char = false;
-        }
+fn descape_string(text: &str) -> String {
+    let mut response = String::new();
+    let mut descape_next_char = false;
+    let mut iterator = text.chars();
+
+    loop {
+        match iterator.next() {
+            Some('t') if descape_next_char => response.push('\t'),
+            Some('r') if descape_next_char => response.push('\r'),
+            Some('n') if descape_next_char => response.push('\n'),
+            Some('\\') if descape_next_char == false => {
+                descape_next_char = true;
+
+                continue;
+            },
+            Some(ch) => response.push(ch),
+            None => break
+        };
 
-        response
+        descape_next_char = false;
     }
 
-    fn exit_run_with_error(&mut self, err: String) -> Result<(), String> {
-        self.error = Some(err.clone());
+    response
+}
 
-        Err(err)
+pub(crate) fn content_bytes(content: &str, format: &utils::TextFormat) -> Result<Vec<u8>, String> {
+    if *format == utils::TextFormat::Text {
+        return Ok(descape_string(content).into_bytes());
     }
+
+    utils::bytes_from_format(content, format)
 }
 
-impl ToString for TestCase {
-    fn to_string(&self) -> String {
-        if let Some(err) = &self.error {
-            return format!("{}...{} {}", self.title(), "Error:".red(), err);
-        }
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use crate::serial::{Serial, PortRegistry};
+    use crate::serial::transport::MockTransport;
+    use crate::utils::TextFormat;
+    use crate::tests::TestStatus;
+    use super::{TestCase, TestStep, MatchMode};
+
+    fn test(input: &str, output: &str) -> TestCase {
+        TestCase::new(String::new(), input.to_string(), output.to_string())
+    }
 
-        if let Some(successful) = self.successful {
-            if successful == false && self.settings.allow_failure.unwrap_or(false) == false {
-                return if let Some(ref response) = self.response {
-                    format!("{}...{}, expected '{}' but received '{}'", self.title(), "Failed".red(), self.output, response)
-                } else {
-                    format!("{}...{}, expected '{}' but received nothing", self.title(), "Failed".red(), self.output)
-                };
-            }
+    #[test]
+    fn run_test_case_with_wrong_response() {
+        let result = test("ping", "pong").run(&mut MockTransport::ports(&[("ping", "pang")]));
 
-            // test passed
-            let repeat = if let Some(count) = self.settings.repeat {
-                format!(" ({}x)", count)
-            } else {
-                String::new()
-            };
+        assert_eq!(result.status, TestStatus::Failed);
+        assert_eq!(result.response, Some(b"pang".to_vec()));
+    }
 
-            let verbose = if self.settings.verbose.unwrap_or(false) {
-                if let Some(ref response) = self.response {
-                    format!(", response: '{}'", response)
-                } else {
-                    format!(", no response")
-                }
-            } else {
-                String::new()
-            };
+    #[test]
+    fn run_test_case_without_response() {
+        let result = test("ping", "pong").run(&mut MockTransport::ports(&[]));
 
-            let result = if successful {
-                format!("{}", "OK".green())
-            } else {
-                format!("{} (failed)", "OK".yellow())
-            };
+        assert_eq!(result.status, TestStatus::Errored);
+        assert_eq!(result.error, Some("Connection timed out".to_string()));
+        assert!(result.timed_out);
+    }
 
-            format!("{}...{}{}{}", self.title(), result, repeat, verbose)
-        } else {
-            format!("{}", self.title())
+    #[test]
+    fn run_test_case_with_invalid_utf8_response() {
+        let mut transport = MockTransport::new();
+        transport.push(b"ping", b"\xFFpong");
+
+        let result = test("ping", "pong").run(&mut transport.into_ports());
+
+        assert_eq!(result.status, TestStatus::Failed);
+        assert_eq!(result.response_text(), Some("\\xFFpong".to_string()));
+    }
+
+    #[test]
+    fn run_repeated_test_case() {
+        let mut ports = MockTransport::ports(&[("ping", "pong"), ("ping", "pong"), ("ping", "pang")]);
+
+        let mut test = test("ping", "pong");
+        test.settings.repeat = Some(2);
+
+        let result = test.run(&mut ports);
+
+        assert_eq!(result.status, TestStatus::Failed);
+        assert_eq!(result.iterations.len(), 3);
+        assert!(!result.iterations[2].successful);
+    }
+
+    #[test]
+    fn run_test_sequence() {
+        let test = TestCase::new_sequence(String::new(), vec!(
+            TestStep::Send("login".to_string(), TextFormat::Text),
+            TestStep::Expect("Password:".to_string(), TextFormat::Text),
+            TestStep::Send("secret".to_string(), TextFormat::Text),
+            TestStep::Expect("OK".to_string(), TextFormat::Text)
+        ));
+
+        let result = test.run(&mut MockTransport::ports(&[("login", "Password:"), ("secret", "Denied")]));
+
+        assert_eq!(result.status, TestStatus::Failed);
+        assert_eq!(result.step, Some(4));
+        assert_eq!(result.failure_message(), Some("step 4: expected 'OK' but received 'Denied'".to_string()));
+    }
+
+    #[test]
+    fn run_send_and_expect_only_tests() {
+        let mut transport = MockTransport::new();
+        transport.push_unsolicited(b"Welcome v2");
+        transport.push(b"reset", b"");
+
+        let mut ports = transport.into_ports();
+
+        assert_eq!(TestCase::new_expect("Banner".to_string(), "Welcome v[0-9]".to_string()).run(&mut ports).status, TestStatus::Passed);
+        assert_eq!(TestCase::new_send("Reset".to_string(), "reset".to_string()).run(&mut ports).status, TestStatus::Passed);
+
+        let result = TestCase::new_expect(String::new(), "Ready".to_string()).run(&mut ports);
+
+        assert_eq!(result.status, TestStatus::Errored);
+        assert_eq!(result.error, Some("Connection timed out".to_string()));
+    }
+
+    #[test]
+    fn match_output_in_modes() {
+        let modes = [
+            (MatchMode::Exact, "OK.*", TestStatus::Failed),
+            (MatchMode::Contains, "OK (1)", TestStatus::Passed),
+            (MatchMode::StartsWith, "c\\r\\n", TestStatus::Passed),
+            (MatchMode::EndsWith, "OK (1)", TestStatus::Failed),
+            (MatchMode::Line, "OK [(][0-9][)]", TestStatus::Passed),
+            (MatchMode::RegexSearch, "OK [(](?P<count>[0-9])", TestStatus::Passed)
+        ];
+
+        for (input, &(match_mode, output, status)) in ["a", "b", "c", "d", "e", "f"].iter().zip(modes.iter()) {
+            let response = format!("{}\r\nOK (1)\r\n> ", input);
+
+            let mut test = test(input, output);
+            test.settings.match_mode = Some(match_mode);
+
+            assert_eq!(test.run(&mut MockTransport::ports(&[(input, &response)])).status, status, "match mode {}", match_mode);
         }
     }
+
+    #[test]
+    fn complete_regex_responses_early() {
+        let mut transport = MockTransport::new();
+        transport.set_chunk_size(2);
+        transport.push(b"a", b"OK 42\r\n");
+        transport.push(b"b", b"ERR 1\r\n");
+
+        let mut ports = transport.into_ports();
+
+        assert_eq!(test("a", "OK [0-9]+\\r\\n").run(&mut ports).status, TestStatus::Passed);
+
+        // reading stops at the first chunk not matching anymore
+        let result = test("b", "OK [0-9]+\\r\\n").run(&mut ports);
+
+        assert_eq!(result.status, TestStatus::Failed);
+        assert_eq!(result.response, Some(b"ER".to_vec()));
+    }
+
+    #[test]
+    fn stop_reading_continuous_data_at_timeout() {
+        let mut transport = MockTransport::new();
+        transport.set_stream(b"log\r\n");
+
+        let mut test = TestCase::new_expect(String::new(), "ready".to_string());
+        test.settings.match_mode = Some(MatchMode::Contains);
+        test.settings.timeout = Some(Duration::from_millis(20));
+
+        let result = test.run(&mut transport.into_ports());
+
+        assert_eq!(result.status, TestStatus::Errored);
+        assert_eq!(result.error, Some("No matching response within 20ms".to_string()));
+        assert!(result.timed_out);
+    }
+
+    #[test]
+    fn run_tests_on_named_ports() {
+        let mut gateway = MockTransport::new();
+        gateway.push(b"pair", b"ok");
+
+        let mut node = MockTransport::new();
+        node.push_unsolicited(b"joined");
+
+        let mut ports = PortRegistry::new();
+        ports.insert("gateway".to_string(), Serial::with_transport(Box::new(gateway)));
+        ports.insert("node".to_string(), Serial::with_transport(Box::new(node)));
+
+        let mut pair = test("pair", "ok");
+        pair.settings.port = Some("gateway".to_string());
+
+        let mut joined = TestCase::new_expect(String::new(), "joined".to_string());
+        joined.settings.port = Some("node".to_string());
+
+        assert_eq!(pair.run(&mut ports).status, TestStatus::Passed);
+        assert_eq!(joined.run(&mut ports).status, TestStatus::Passed);
+
+        // tests without port run on the default port
+        let result = test("ping", "pong").run(&mut ports);
+
+        assert_eq!(result.status, TestStatus::Errored);
+        assert_eq!(result.error, Some("No default port is open".to_string()));
+    }
 }
@@ -26,29 +26,57 @@
  * SOFTWARE.
  */
 
-use crate::serial::Serial;
+use std::fmt;
+use serde::Serialize;
+use crate::serial::PortRegistry;
+use crate::serial::settings::{Settings, PartialSettings};
+use crate::tests::result::{SuiteResult, TestResult, TestStatus};
+use crate::tests::variables::Variables;
 
 pub use crate::tests::test_case::{TestCase, TestCaseSettings};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct TestSuiteSettings {
-    pub stop_on_failure: bool
+    pub stop_on_failure: bool,
+    /// Serial settings the port of the group is reconfigured with while the group runs.
+    pub serial: PartialSettings
+}
+
+
+/// Point at which a hook test of a group is run.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TestHook {
+    /// Run once before the first test of the group.
+    Setup,
+    /// Run once after the last test of the group, even if the group was stopped.
+    Teardown,
+    /// Run before each test of the group.
+    SetupEach,
+    /// Run after each test of the group.
+    TeardownEach
 }
 
-impl Default for TestSuiteSettings {
-    fn default() -> TestSuiteSettings {
-        TestSuiteSettings {
-            stop_on_failure: false
+impl fmt::Display for TestHook {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            TestHook::Setup => formatter.write_str("setup"),
+            TestHook::Teardown => formatter.write_str("teardown"),
+            TestHook::SetupEach => formatter.write_str("setup-each"),
+            TestHook::TeardownEach => formatter.write_str("teardown-each")
         }
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct TestSuite {
     pub name: String,
     pub settings: TestSuiteSettings,
     pub test_settings: TestCaseSettings,
-    tests: Vec<TestCase>
+    /// Variables set before the first test, tests of the group share one scope.
+    pub variables: Variables,
+    tests: Vec<TestCase>,
+    hooks: Vec<(TestHook, TestCase)>
 }
 
 impl TestSuite {
@@ -57,7 +85,9 @@ impl TestSuite {
             name,
             settings: Default::default(),
             test_settings: Default::default(),
-            tests: Vec::new()
+            variables: Variables::new(),
+            tests: Vec::new(),
+            hooks: Vec::new()
         }
     }
 
@@ -66,7 +96,9 @@ impl TestSuite {
             name,
             settings,
             test_settings,
-            tests: Vec::new()
+            variables: Variables::new(),
+            tests: Vec::new(),
+            hooks: Vec::new()
         }
     }
 
@@ -78,43 +110,125 @@ impl TestSuite {
         }
     }
 
-    pub fn run(&mut self, serial: &mut Serial) -> Result<bool, String> {
-        for test in self.tests.iter_mut() {
-            test.run(serial)?;
+    /// Add a hook test run at the given point of the group.
+    pub fn push_hook(&mut self, hook: TestHook, mut test: TestCase) {
+        test.settings.merge_weak(&self.test_settings);
 
-            if self.settings.stop_on_failure && test.is_successful() == Some(false) {
-                return Ok(false);
-            }
-        }
+        self.hooks.push((hook, test));
+    }
 
-        Ok(true)
+    pub fn run(&self, ports: &mut PortRegistry) -> SuiteResult {
+        self.run_with_callback(ports, |_, _| ())
     }
 
-    pub fn run_and_print(&mut self, serial: &mut Serial) -> bool {
+    pub fn run_and_print(&self, ports: &mut PortRegistry) -> SuiteResult {
         let show_title = self.name != "";
 
         if show_title {
             println!("{}", self.title());
         }
 
-        for test in self.tests.iter_mut() {
+        self.run_with_callback(ports, |test, result| {
+            // hooks are only shown if they fail
+            if result.hook.is_some() && result.is_successful() {
+                return;
+            }
+
             if show_title {
                 print!("\t");
             }
 
-            let result = match test.run(serial) {
-                Ok(_) => test.is_successful(),
-                Err(_) => Some(false)
+            if let Some(hook) = result.hook {
+                print!("{}: ", hook);
+            }
+
+            println!("{}", test.format_result(result));
+        })
+    }
+
+    /// Run all tests calling the callback after each test and hook. Tests not run because of `stop-on-failure` or
+    /// a failed setup hook are skipped. Teardown hooks are always run.
+    pub fn run_with_callback<F: FnMut(&TestCase, &TestResult)>(&self, ports: &mut PortRegistry, mut callback: F) -> SuiteResult {
+        let mut result = SuiteResult {
+            name: self.name.clone(),
+            tests: Vec::new(),
+            hooks: Vec::new(),
+            stopped: false
+        };
+
+        let mut variables = self.variables.clone();
+
+        // reconfigure the port before the setup, a failure skips the whole group including its hooks
+        let reconfigured = self.reconfigure_port(ports);
+
+        let setup_error = match reconfigured {
+            Ok(_) => self.run_hooks(TestHook::Setup, ports, &mut variables, &mut result.hooks, &mut callback),
+            Err(ref err) => Some(err.clone())
+        };
+
+        for test in &self.tests {
+            if result.stopped {
+                result.tests.push(test.skip());
+
+                continue;
+            }
+
+            if let Some(ref err) = setup_error {
+                let mut test_result = test.skip();
+                test_result.error = Some(err.clone());
+
+                result.tests.push(test_result);
+
+                continue;
+            }
+
+            let test_result = if let Some(err) = self.run_hooks(TestHook::SetupEach, ports, &mut variables, &mut result.hooks, &mut callback) {
+                let mut test_result = test.skip();
+                test_result.error = Some(err);
+
+                test_result
+            } else {
+                test.run_with_variables(ports, &mut variables)
             };
 
-            println!("{}", test.to_string());
+            callback(test, &test_result);
+
+            self.run_hooks(TestHook::TeardownEach, ports, &mut variables, &mut result.hooks, &mut callback);
 
-            if result != Some(true) && self.settings.stop_on_failure {
-                return false;
+            if test_result.status != TestStatus::Passed && self.settings.stop_on_failure {
+                result.stopped = true;
             }
+
+            result.tests.push(test_result);
         }
 
-        true
+        if let Ok(old_settings) = reconfigured {
+            self.run_hooks(TestHook::Teardown, ports, &mut variables, &mut result.hooks, &mut callback);
+
+            if let (Some(settings), Ok(serial)) = (old_settings, ports.get(self.test_settings.port.as_deref())) {
+                let _ = serial.set_settings(settings);
+            }
+        }
+
+        result
+    }
+
+    /// Get the result of the test suite without running it.
+    pub fn skip(&self) -> SuiteResult {
+        SuiteResult {
+            name: self.name.clone(),
+            tests: self.tests.iter().map(|test| test.skip()).collect(),
+            hooks: Vec::new(),
+            stopped: false
+        }
+    }
+
+    pub fn tests(&self) -> &[TestCase] {
+        &self.tests
+    }
+
+    pub fn hooks(&self) -> &[(TestHook, TestCase)] {
+        &self.hooks
     }
 
     pub fn len(&self) -> usize {
@@ -125,28 +239,54 @@ impl TestSuite {
         self.tests.is_empty()
     }
 
-    pub fn failed(&self) -> usize {
-        self.count_tests(false)
-    }
+    /// Apply the serial settings of the group to its port.
+    ///
+    /// Returns the settings of the port before, to be restored after the group.
+    fn reconfigure_port(&self, ports: &mut PortRegistry) -> Result<Option<Settings>, String> {
+        if self.settings.serial.is_empty() {
+            return Ok(None);
+        }
+
+        let serial = ports.get(self.test_settings.port.as_deref())?;
+        let old_settings = serial.settings().clone();
 
-    pub fn successful(&self) -> usize {
-        self.count_tests(true)
+        let mut settings = old_settings.clone();
+        self.settings.serial.apply(&mut settings);
+
+        match serial.set_settings(settings) {
+            Ok(_) => Ok(Some(old_settings)),
+            Err(e) => Err(format!("Unable to reconfigure port: {}", e))
+        }
     }
 
-    fn count_tests(&self, success: bool) -> usize {
-        let mut count = 0;
+    /// Run all hooks of the given kind. Setup hooks stop at the first failure, teardown hooks are all run.
+    ///
+    /// Returns the failure message of the first failed hook.
+    fn run_hooks<F: FnMut(&TestCase, &TestResult)>(&self, hook: TestHook, ports: &mut PortRegistry, variables: &mut Variables, results: &mut Vec<TestResult>, callback: &mut F) -> Option<String> {
+        let mut error = None;
 
-        for test in &self.tests {
-            if test.is_successful().is_none() && test.error().is_none() {
+        for &(kind, ref test) in &self.hooks {
+            if kind != hook {
                 continue;
             }
 
-            if (test.is_successful().unwrap_or(false) || test.settings.allow_failure.unwrap_or(false)) == success {
-                count += 1;
+            let mut test_result = test.run_with_variables(ports, variables);
+            test_result.hook = Some(hook);
+
+            callback(test, &test_result);
+
+            if error.is_none() && test_result.is_successful() == false {
+                error = Some(format!("{} '{}' failed: {}", hook, test.to_string(), test_result.failure_message().unwrap_or_default()));
+            }
+
+            results.push(test_result);
+
+            if error.is_some() && (hook == TestHook::Setup || hook == TestHook::SetupEach) {
+                break;
             }
         }
 
-        count
+        error
     }
 
     fn title(&self) -> String {
@@ -164,6 +304,14 @@ impl ToString for TestSuite {
             result.push_str(format!("{}\n", self.title()).as_str());
         }
 
+        for &(hook, ref test) in &self.hooks {
+            if show_group {
+                result.push('\t');
+            }
+
+            result.push_str(format!("{}: {}\n", hook, test.to_string()).as_str());
+        }
+
         for test in &self.tests {
             if show_group {
                 result.push('\t');
@@ -175,3 +323,94 @@ impl ToString for TestSuite {
         result
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::serial::transport::MockTransport;
+    use crate::tests::{TestCase, TestStatus};
+    use super::{TestSuite, TestHook};
+
+    fn test(input: &str, output: &str) -> TestCase {
+        TestCase::new(String::new(), input.to_string(), output.to_string())
+    }
+
+    #[test]
+    fn run_test_suite() {
+        let mut ports = MockTransport::ports(&[("ping\n", "pong"), ("version\n", "1.0.0")]);
+
+        let mut test_suite = TestSuite::new(String::new());
+        test_suite.push(test("ping\\n", "pong"));
+        test_suite.push(test("version\\n", "[0-9]+.[0-9]+.[0-9]+"));
+
+        let result = test_suite.run(&mut ports);
+
+        assert_eq!(result.successful(), 2);
+        assert!(!result.stopped);
+    }
+
+    #[test]
+    fn run_test_suite_with_hooks() {
+        let mut ports = MockTransport::ports(&[
+            ("login", "ok"), ("reset", "ok"), ("a", "1"), ("reset", "ok"), ("b", "0"), ("logout", "bye")
+        ]);
+
+        let mut test_suite = TestSuite::new("Group".to_string());
+        test_suite.settings.stop_on_failure = true;
+        test_suite.push_hook(TestHook::Setup, test("login", "ok"));
+        test_suite.push_hook(TestHook::SetupEach, test("reset", "ok"));
+        test_suite.push_hook(TestHook::Teardown, test("logout", "bye"));
+        test_suite.push(test("a", "1"));
+        test_suite.push(test("b", "1"));
+        test_suite.push(test("c", "1"));
+
+        let result = test_suite.run(&mut ports);
+
+        assert!(result.stopped);
+        assert_eq!(result.count(TestStatus::Skipped), 1);
+        assert_eq!(result.hooks.len(), 4);
+        assert_eq!(result.hooks[3].hook, Some(TestHook::Teardown));
+        assert_eq!(result.hooks[3].status, TestStatus::Passed);
+    }
+
+    #[test]
+    fn skip_tests_after_failed_setup() {
+        let mut ports = MockTransport::ports(&[("login", "denied"), ("logout", "bye")]);
+
+        let mut test_suite = TestSuite::new(String::new());
+        test_suite.push_hook(TestHook::Setup, TestCase::new("Login".to_string(), "login".to_string(), "ok".to_string()));
+        test_suite.push_hook(TestHook::Teardown, test("logout", "bye"));
+        test_suite.push(test("a", "1"));
+
+        let result = test_suite.run(&mut ports);
+
+        assert_eq!(result.tests[0].status, TestStatus::Skipped);
+        assert!(result.tests[0].error.as_ref().unwrap().starts_with("setup 'Login \"login\"' failed"));
+        assert_eq!(result.failed(), 1);
+        assert_eq!(result.hooks[1].status, TestStatus::Passed);
+    }
+
+    #[test]
+    fn reconfigure_port_for_group() {
+        let mut ports = MockTransport::ports(&[("baud 57600", "ok"), ("ping", "pong")]);
+
+        let mut test_suite = TestSuite::new(String::new());
+        test_suite.settings.serial.baud_rate = Some(57600);
+        test_suite.push(test("baud 57600", "ok"));
+        test_suite.push(test("ping", "pong"));
+
+        let result = test_suite.run(&mut ports);
+
+        assert_eq!(result.successful(), 2);
+
+        // settings of the port are restored after the group
+        assert_eq!(ports.get(None).unwrap().settings().baud_rate, 9600);
+
+        // groups on ports which are not open are skipped
+        test_suite.test_settings.port = Some("node".to_string());
+
+        let result = test_suite.run(&mut ports);
+
+        assert_eq!(result.tests[0].status, TestStatus::Skipped);
+        assert_eq!(result.tests[0].error, Some("Port 'node' is not open".to_string()));
+    }
+}
@@ -0,0 +1,116 @@
+/*
+ * File: tests/variables.rs
+ * Date: 15.10.2026
+ * Author: agent
+ * 
+ * MIT License
+ * 
+ * Copyright (c) 2026 MarkAtk
+ * 
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+ * of the Software, and to permit persons to whom the Software is furnished to do
+ * so, subject to the following conditions:
+ * 
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ * 
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+use std::collections::HashMap;
+
+/// Script variables by name, set by named capture groups of output patterns.
+pub type Variables = HashMap<String, String>;
+
+/// Replace each `${name}` in the text with the value returned by lookup. Escaped references `\${name}` are kept as is.
+pub fn interpolate<F: Fn(&str) -> Result<String, String>>(text: &str, lookup: F) -> Result<String, String> {
+    let mut result = String::new();
+    let mut iterator = text.chars().peekable();
+
+    while let Some(ch) = iterator.next() {
+        match ch {
+            '\\' => {
+                result.push(ch);
+
+                if let Some(next_ch) = iterator.next() {
+                    result.push(next_ch);
+                }
+            },
+            '$' if iterator.peek() == Some(&'{') => {
+                iterator.next();
+
+                let mut name = String::new();
+                let mut closed = false;
+
+                for name_ch in iterator.by_ref() {
+                    if name_ch == '}' {
+                        closed = true;
+
+                        break;
+                    }
+
+                    name.push(name_ch);
+                }
+
+                if closed == false {
+                    return Err(format!("Missing closing '}}' of variable '{}'", name));
+                }
+
+                result.push_str(&lookup(name.trim())?);
+            },
+            _ => result.push(ch)
+        };
+    }
+
+    Ok(result)
+}
+
+/// Get the value of a variable or an error if it is not set.
+pub fn lookup(variables: &Variables, name: &str) -> Result<String, String> {
+    match variables.get(name) {
+        Some(value) => Ok(value.clone()),
+        None => Err(format!("Undefined variable '{}'", name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::serial::transport::MockTransport;
+    use crate::tests::{TestCase, TestSuite};
+    use super::{interpolate, lookup, Variables};
+
+    #[test]
+    fn interpolate_variables() {
+        let mut variables = Variables::new();
+        variables.insert("token".to_string(), "a1b2".to_string());
+
+        assert_eq!(interpolate("login ${token}\\n", |name| lookup(&variables, name)), Ok("login a1b2\\n".to_string()));
+        assert_eq!(interpolate("\\${token} $token", |name| lookup(&variables, name)), Ok("\\${token} $token".to_string()));
+        assert_eq!(interpolate("${serial}", |name| lookup(&variables, name)), Err("Undefined variable 'serial'".to_string()));
+    }
+
+    #[test]
+    fn capture_and_interpolate_variables() {
+        let mut ports = MockTransport::ports(&[("login", "token=a1b2"), ("auth a1b2", "a1b2 ok"), ("version", "1.0")]);
+
+        let mut test_suite = TestSuite::new(String::new());
+        test_suite.variables.insert("version".to_string(), "1.0".to_string());
+        test_suite.push(TestCase::new(String::new(), "login".to_string(), "token=(?P<token>[0-9a-f]+)".to_string()));
+        test_suite.push(TestCase::new(String::new(), "auth ${token}".to_string(), "${token} ok".to_string()));
+        test_suite.push(TestCase::new(String::new(), "version".to_string(), "${version}".to_string()));
+
+        let result = test_suite.run(&mut ports);
+
+        assert_eq!(result.successful(), 3);
+        assert_eq!(result.tests[1].input, "auth a1b2");
+    }
+}
@@ -28,7 +28,9 @@
 
 use std::str;
 
-#[derive(PartialEq, Debug)]
+pub mod checksum;
+
+#[derive(PartialEq, Debug, Clone, Copy)]
 pub enum TextFormat {
     Text,
     Binary = 2,
@@ -51,6 +53,14 @@ pub fn bytes_from_binary_string(original_text: &str) -> Result<Vec<u8>, String>
     bytes_from_radix_string(&text, 2)
 }
 
+pub fn bytes_from_format(text: &str, text_format: &TextFormat) -> Result<Vec<u8>, String> {
+    match text_format {
+        TextFormat::Binary => bytes_from_binary_string(text),
+        TextFormat::Hex => bytes_from_hex_string(&checksum::expand_helpers(text, text_format)?),
+        _ => Ok(text.as_bytes().to_vec())
+    }
+}
+
 pub fn bytes_from_radix_string(text: &str, radix: u32) -> Result<Vec<u8>, String> {
     let mut bytes: Vec<u8> = Vec::new();
 
@@ -70,7 +80,7 @@ pub fn bytes_from_radix_string(text: &str, radix: u32) -> Result<Vec<u8>, String
 
 pub fn radix_string(buffer: &[u8], text_format: &TextFormat) -> String {
     if *text_format == TextFormat::Text {
-        return str::from_utf8(buffer).unwrap().to_string();
+        return String::from_utf8_lossy(buffer).to_string();
     }
 
     let mut text = String::new();
@@ -88,6 +98,44 @@ pub fn radix_string(buffer: &[u8], text_format: &TextFormat) -> String {
     text
 }
 
+/// Render bytes for display. Invalid UTF-8 and non-printable characters are shown as escape sequences, e.g. `\r` or `\xFF`.
+pub fn escape_bytes(buffer: &[u8]) -> String {
+    let mut text = String::new();
+    let mut remaining = buffer;
+
+    while remaining.is_empty() == false {
+        let (valid, invalid) = match str::from_utf8(remaining) {
+            Ok(valid) => (valid, 0),
+            Err(e) => {
+                let valid = str::from_utf8(&remaining[..e.valid_up_to()]).unwrap();
+
+                (valid, e.error_len().unwrap_or(remaining.len() - e.valid_up_to()))
+            }
+        };
+
+        for ch in valid.chars() {
+            match ch {
+                '\r' => text.push_str("\\r"),
+                '\n' => text.push_str("\\n"),
+                '\t' => text.push_str("\\t"),
+                '\\' => text.push_str("\\\\"),
+                ch if ch.is_control() => text.push_str(&format!("\\x{:02X}", ch as u32)),
+                ch => text.push(ch)
+            };
+        }
+
+        let start = valid.len();
+
+        for b in &remaining[start..start + invalid] {
+            text.push_str(&format!("\\x{:02X}", b));
+        }
+
+        remaining = &remaining[start + invalid..];
+    }
+
+    text
+}
+
 pub fn print_radix_string(buffer: &[u8], text_format: &TextFormat, row_entries: &mut u32) {
     let max_row_entries = match text_format {
         TextFormat::Binary => 10,
@@ -121,3 +169,15 @@ pub fn escape_text(text: String) -> String {
 
     text
 }
+
+#[cfg(test)]
+mod tests {
+    use super::escape_bytes;
+
+    #[test]
+    fn escape_non_printable_bytes() {
+        assert_eq!(escape_bytes(b"OK\r\n"), "OK\\r\\n");
+        assert_eq!(escape_bytes(b"\xFFa\x00"), "\\xFFa\\x00");
+        assert_eq!(escape_bytes("25\u{b0}C".as_bytes()), "25\u{b0}C");
+    }
+}
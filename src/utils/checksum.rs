@@ -0,0 +1,220 @@
+/*
+ * File: utils/checksum.rs
+ * Date: 16.10.2026
+ * Author: agent
+ * 
+ * MIT License
+ * 
+ * Copyright (c) 2026 MarkAtk
+ * 
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+ * of the Software, and to permit persons to whom the Software is furnished to do
+ * so, subject to the following conditions:
+ * 
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ * 
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+use super::{TextFormat, bytes_from_format, radix_string};
+
+/// Names of all content helpers, longer names first so they are not taken for their prefix.
+pub const HELPERS: &[&str] = &["crc16_ccitt", "crc16", "crc8", "xor", "sum8", "len"];
+
+/// CRC-8 with polynomial 0x07 and initial value 0x00 (CRC-8/SMBUS).
+pub fn crc8(data: &[u8]) -> u8 {
+    let mut crc = 0u8;
+
+    for byte in data {
+        crc ^= byte;
+
+        for _ in 0..8 {
+            crc = if crc & 0x80 != 0 { (crc << 1) ^ 0x07 } else { crc << 1 };
+        }
+    }
+
+    crc
+}
+
+/// CRC-16 as used by Modbus RTU, reflected polynomial 0xA001 and initial value 0xFFFF.
+pub fn crc16_modbus(data: &[u8]) -> u16 {
+    let mut crc = 0xFFFFu16;
+
+    for byte in data {
+        crc ^= u16::from(*byte);
+
+        for _ in 0..8 {
+            crc = if crc & 0x0001 != 0 { (crc >> 1) ^ 0xA001 } else { crc >> 1 };
+        }
+    }
+
+    crc
+}
+
+/// CRC-16 with polynomial 0x1021 and initial value 0xFFFF (CRC-16/CCITT-FALSE).
+pub fn crc16_ccitt(data: &[u8]) -> u16 {
+    let mut crc = 0xFFFFu16;
+
+    for byte in data {
+        crc ^= u16::from(*byte) << 8;
+
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 { (crc << 1) ^ 0x1021 } else { crc << 1 };
+        }
+    }
+
+    crc
+}
+
+/// XOR of all bytes.
+pub fn xor(data: &[u8]) -> u8 {
+    data.iter().fold(0, |checksum, byte| checksum ^ byte)
+}
+
+/// Sum of all bytes modulo 256.
+pub fn sum8(data: &[u8]) -> u8 {
+    data.iter().fold(0, |checksum, byte| checksum.wrapping_add(*byte))
+}
+
+/// Get the data of a helper, the data followed by its checksum or prefixed with its length.
+pub fn apply_helper(name: &str, data: &[u8]) -> Result<Vec<u8>, String> {
+    let mut result = Vec::new();
+
+    match name {
+        "len" => {
+            if data.len() > 255 {
+                return Err("Content of len() is longer than 255 bytes".to_string());
+            }
+
+            result.push(data.len() as u8);
+            result.extend_from_slice(data);
+        },
+        "crc8" => {
+            result.extend_from_slice(data);
+            result.push(crc8(data));
+        },
+        "crc16" => {
+            // Modbus sends the low byte first
+            let crc = crc16_modbus(data);
+
+            result.extend_from_slice(data);
+            result.push((crc & 0xFF) as u8);
+            result.push((crc >> 8) as u8);
+        },
+        "crc16_ccitt" => {
+            let crc = crc16_ccitt(data);
+
+            result.extend_from_slice(data);
+            result.push((crc >> 8) as u8);
+            result.push((crc & 0xFF) as u8);
+        },
+        "xor" => {
+            result.extend_from_slice(data);
+            result.push(xor(data));
+        },
+        "sum8" => {
+            result.extend_from_slice(data);
+            result.push(sum8(data));
+        },
+        _ => return Err(format!("Unknown content helper '{}'", name))
+    };
+
+    Ok(result)
+}
+
+/// Replace all helper calls in hex content by their data in hex, e.g. `AA crc8(0102)`. Helpers can be nested,
+/// content outside of helpers and content in other formats is kept unchanged.
+pub fn expand_helpers(text: &str, text_format: &TextFormat) -> Result<String, String> {
+    if *text_format != TextFormat::Hex {
+        return Ok(text.to_string());
+    }
+
+    let mut result = String::new();
+    let mut remaining = text;
+
+    while remaining.is_empty() == false {
+        let helper = HELPERS.iter().find(|name| remaining.starts_with(&format!("{}(", name)));
+
+        let name = match helper {
+            Some(name) => *name,
+            None => {
+                let ch = remaining.chars().next().unwrap();
+
+                if ch == ')' {
+                    return Err("Unexpected ')' in content".to_string());
+                }
+
+                result.push(ch);
+                remaining = &remaining[ch.len_utf8()..];
+
+                continue;
+            }
+        };
+
+        // find closing parenthesis of the call
+        let arguments_start = name.len() + 1;
+        let mut depth = 1;
+        let mut arguments_end = None;
+
+        for (index, ch) in remaining[arguments_start..].char_indices() {
+            match ch {
+                '(' => depth += 1,
+                ')' => depth -= 1,
+                _ => ()
+            };
+
+            if depth == 0 {
+                arguments_end = Some(arguments_start + index);
+
+                break;
+            }
+        }
+
+        let arguments_end = match arguments_end {
+            Some(end) => end,
+            None => return Err(format!("Missing ')' of {}()", name))
+        };
+
+        let arguments = expand_helpers(&remaining[arguments_start..arguments_end], text_format)?;
+        let data = bytes_from_format(&arguments, text_format)?;
+
+        result.push_str(&radix_string(&apply_helper(name, &data)?, text_format));
+        remaining = &remaining[arguments_end + 1..];
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn calculate_checksums() {
+        let data = b"123456789";
+
+        assert_eq!(crc8(data), 0xF4);
+        assert_eq!(crc16_modbus(data), 0x4B37);
+        assert_eq!(crc16_ccitt(data), 0x29B1);
+        assert_eq!(xor(&[0x01, 0x02, 0x04]), 0x07);
+        assert_eq!(sum8(&[0xFF, 0x02]), 0x01);
+    }
+
+    #[test]
+    fn expand_content_helpers() {
+        assert_eq!(expand_helpers("AA crc16(0103 0000 0001)", &TextFormat::Hex), Ok("AA 010300000001840A".to_string()));
+        assert_eq!(expand_helpers("AA xor(len(0102))", &TextFormat::Hex), Ok("AA 02010201".to_string()));
+        assert_eq!(expand_helpers("sum8(0102)", &TextFormat::Text), Ok("sum8(0102)".to_string()));
+        assert!(expand_helpers("crc8(0102", &TextFormat::Hex).is_err());
+    }
+}
@@ -26,26 +26,13 @@
  * SOFTWARE.
  */
 
-use std::io;
-use std::fs::File;
-
 use clap::{ArgMatches, SubCommand, Arg, App};
 
-use serial_unit_testing::parser;
-
-pub fn run(matches: &ArgMatches) -> Result<(), String> {
-    let filename = matches.value_of("file").unwrap();
+use crate::commands;
+use crate::error::Error;
 
-    let mut file = match File::open(filename) {
-        Ok(file) => file,
-        Err(ref e) if e.kind() == io::ErrorKind::NotFound => return Err("File not found".to_string()), 
-        Err(e) => return Err(format!("{}", e))
-    };
-
-    let test_suites = match parser::parse_file(&mut file) {
-        Ok(test_suites) => test_suites,
-        Err(e) => return Err(format!("Unable to parse file: {}", e))
-    };
+pub fn run(matches: &ArgMatches) -> Result<(), Error> {
+    let test_suites = commands::parse_script(matches, Default::default())?.test_suites;
 
     match matches.occurrences_of("verbose") {
         1 => {
@@ -68,7 +55,7 @@ pub fn command<'a>() -> App<'a, 'a> {
     SubCommand::with_name("verify")
         .about("Verify a script can be parsed")
         .arg(Arg::with_name("file")
-            .help("Script to verify")
+            .help("Script to verify, - to read the script from stdin")
             .required(true)
             .takes_value(true))
         .arg(Arg::with_name("verbose")